@@ -1,6 +1,7 @@
 use auto_impl_ops::*;
 use num_traits::*;
 use std::ops::*;
+use std::ops::{Add as Plus, AddAssign as PlusAssign};
 
 #[derive(Clone, Default)]
 struct A<T>(T);
@@ -8,7 +9,7 @@ struct A<T>(T);
 struct B(i32);
 
 // from assign_ref
-#[auto_ops]
+#[auto_ops(sum)]
 impl<M> AddAssign<&A<M>> for A<M>
 where
     M: Sized + Zero + for<'x> AddAssign<&'x M>,
@@ -44,7 +45,7 @@ where
     }
 }
 
-#[auto_ops]
+#[auto_ops(commutative(f64, i32))]
 impl<R> MulAssign<&R> for A<R>
 where
     R: Sized + Zero + for<'x> MulAssign<&'x R>,
@@ -64,18 +65,37 @@ where
     }
 }
 
-#[auto_ops]
+#[auto_ops(sum(B(0)), lift, commutative)]
 impl AddAssign<&B> for B {
     fn add_assign(&mut self, other: &Self) {
         self.0 += &other.0;
     }
 }
 
-// from ref_ref
 #[auto_ops]
+impl<M> Neg for A<M>
+where
+    M: Neg<Output = M>,
+{
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        A(-self.0)
+    }
+}
+
+#[auto_ops]
+impl Not for &B {
+    type Output = B;
+    fn not(self) -> B {
+        B(!self.0)
+    }
+}
+
+// from ref_ref
+#[auto_ops(product(A(M::one())))]
 impl<M> Mul for &A<M>
 where
-    M: Sized + Zero,
+    M: Sized + Zero + One,
     for<'x> &'x M: Mul<Output = M>,
 {
     type Output = A<M>;
@@ -84,6 +104,18 @@ where
     }
 }
 
+// `product` generates the assign variants used by `Product` even if they are not listed
+#[derive(Clone)]
+struct Pr(i32);
+
+#[auto_ops(val_val, product(Pr(1)))]
+impl<'a> Mul<&'a Pr> for &'a Pr {
+    type Output = Pr;
+    fn mul(self, other: &Pr) -> Pr {
+        Pr(self.0 * other.0)
+    }
+}
+
 // from val_ref
 #[auto_ops]
 impl<M> Div<&A<M>> for A<M>
@@ -230,6 +262,89 @@ where
     }
 }
 
+#[derive(Clone, AutoOps)]
+#[ops(Add, Sub, Mul)]
+struct G<T> {
+    x: T,
+    y: T,
+    #[ops(skip(Mul))]
+    z: T,
+    marker: std::marker::PhantomData<T>,
+}
+
+#[derive(Clone)]
+struct H<T>(T);
+
+#[auto_ops(derive_ops(Sub = Add + Neg))]
+impl<T> AddAssign<&H<T>> for H<T>
+where
+    T: for<'x> AddAssign<&'x T>,
+{
+    fn add_assign(&mut self, other: &Self) {
+        self.0 += &other.0;
+    }
+}
+
+#[auto_ops]
+impl<T> Neg for H<T>
+where
+    T: Neg<Output = T>,
+{
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        H(-self.0)
+    }
+}
+
+#[derive(Clone)]
+struct Set(u64);
+
+#[auto_ops(derive_ops(Sub = BitAnd + Not))]
+impl BitAndAssign<&Set> for Set {
+    fn bitand_assign(&mut self, other: &Self) {
+        self.0 &= other.0;
+    }
+}
+
+#[auto_ops]
+impl Not for &Set {
+    type Output = Set;
+    fn not(self) -> Set {
+        Set(!self.0)
+    }
+}
+
+#[derive(Clone)]
+struct V<T> {
+    x: T,
+    y: T,
+}
+
+#[auto_ops(broadcast(T))]
+impl<T> AddAssign<&V<T>> for V<T>
+where
+    T: for<'x> AddAssign<&'x T>,
+{
+    fn add_assign(&mut self, other: &Self) {
+        self.x += &other.x;
+        self.y += &other.y;
+    }
+}
+
+#[auto_ops(broadcast(T))]
+impl<T> Mul for &V<T>
+where
+    for<'x> &'x T: Mul<Output = T>,
+{
+    type Output = V<T>;
+    fn mul(self, other: Self) -> Self::Output {
+        V {
+            x: &self.x * &other.x,
+            y: &self.y * &other.y,
+        }
+    }
+}
+
 struct Vector2<T> {
     x: T,
     y: T,
@@ -267,4 +382,181 @@ where
     }
 }
 
-fn main() {}
+#[auto_ops(custom(trait = Pow, method = pow))]
+impl Pow<&B> for &B {
+    type Output = B;
+    fn pow(self, other: &B) -> Self::Output {
+        B(self.0.pow(other.0 as u32))
+    }
+}
+
+#[derive(Clone)]
+struct I(i32);
+
+#[auto_ops(custom(trait = Plus, method = add, assign = PlusAssign, assign_method = add_assign))]
+impl PlusAssign<&I> for I {
+    fn add_assign(&mut self, other: &Self) {
+        self.0 += other.0;
+    }
+}
+
+#[derive(Clone, PartialEq, PartialOrd)]
+struct Meter(f64);
+#[derive(Clone, PartialEq, PartialOrd)]
+struct Feet(f64);
+
+#[auto_ops]
+impl PartialEq<Feet> for Meter {
+    fn eq(&self, other: &Feet) -> bool {
+        self.0 == other.0 * 0.3048
+    }
+}
+
+#[auto_ops]
+impl PartialOrd<Feet> for Meter {
+    fn partial_cmp(&self, other: &Feet) -> Option<std::cmp::Ordering> {
+        self.0.partial_cmp(&(other.0 * 0.3048))
+    }
+}
+
+#[derive(Clone, Default)]
+struct W(Vec<f64>);
+
+#[auto_ops(lazy)]
+impl AddAssign<&W> for W {
+    fn add_assign(&mut self, other: &Self) {
+        for (x, y) in self.0.iter_mut().zip(other.0.iter()) {
+            *x += y;
+        }
+    }
+}
+
+#[derive(Clone)]
+struct J<M>(M);
+
+#[auto_ops(borrow_rhs, strict, allow_clone(ref_ref))]
+impl<M> SubAssign<&J<M>> for J<M>
+where
+    M: for<'x> SubAssign<&'x M>,
+{
+    fn sub_assign(&mut self, other: &Self) {
+        self.0 -= &other.0;
+    }
+}
+
+#[derive(Clone, Default)]
+struct L<R>(Vec<R>);
+
+impl<R> L<R> {
+    #[allow(dead_code)]
+    fn mul_assign(&mut self, _: usize) {}
+}
+
+#[auto_ops(broadcast(R))]
+impl<R> MulAssign<&L<R>> for L<R>
+where
+    R: for<'x> MulAssign<&'x R>,
+{
+    fn mul_assign(&mut self, other: &Self) {
+        for (x, y) in self.0.iter_mut().zip(other.0.iter()) {
+            *x *= y;
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct N<T>(T);
+
+#[auto_ops]
+impl<T> AddAssign<&Self> for N<T>
+where
+    Self: std::fmt::Debug,
+    T: for<'x> AddAssign<&'x T>,
+{
+    fn add_assign(&mut self, other: &Self) {
+        self.0 += &other.0;
+    }
+}
+
+macro_rules! impl_scale {
+    ($t:ty, $r:ty) => {
+        #[auto_ops(commutative)]
+        impl Mul<$r> for $r {
+            type Output = $t;
+            fn mul(self, other: $r) -> Self::Output {
+                Scale(self.0 * other.0)
+            }
+        }
+    };
+}
+
+#[derive(Clone, Copy)]
+struct Scale(f64);
+impl_scale!(Scale, &Scale);
+
+#[derive(Clone)]
+struct P(Vec<u32>);
+
+#[auto_ops(take = placeholder(P(Vec::new())))]
+impl Shl<&u32> for P {
+    type Output = P;
+    fn shl(self, other: &u32) -> P {
+        P(self.0.into_iter().map(|x| x << other).collect())
+    }
+}
+
+#[auto_ops(take = clone)]
+impl Shr<&u32> for P {
+    type Output = P;
+    fn shr(self, other: &u32) -> P {
+        P(self.0.into_iter().map(|x| x >> other).collect())
+    }
+}
+
+#[auto_ops(take = replace_with)]
+impl BitXor<&u32> for P {
+    type Output = P;
+    fn bitxor(self, other: &u32) -> P {
+        P(self.0.into_iter().map(|x| x ^ other).collect())
+    }
+}
+
+mod reexport {
+    pub use auto_impl_ops as ops;
+}
+
+#[derive(Clone)]
+struct Q(String);
+
+#[reexport::ops::auto_ops(take = take_mut, crate = reexport::ops)]
+impl Add<&Q> for Q {
+    type Output = Q;
+    fn add(self, other: &Q) -> Q {
+        Q(self.0 + &other.0)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Fixed(i32);
+#[derive(Clone, Copy)]
+struct Wide(i64);
+
+impl From<Wide> for Fixed {
+    fn from(x: Wide) -> Self {
+        Fixed((x.0 >> 16) as i32)
+    }
+}
+
+#[auto_ops(output_into)]
+impl Mul<&Fixed> for &Fixed {
+    type Output = Wide;
+    fn mul(self, other: &Fixed) -> Wide {
+        Wide(self.0 as i64 * other.0 as i64)
+    }
+}
+
+fn main() {
+    let w = W(vec![1.0, 2.0]);
+    let x: W = (&w + (&w + &w)).into();
+    assert_eq!(x.0, vec![3.0, 6.0]);
+}
@@ -0,0 +1,68 @@
+use auto_impl_ops::*;
+use num_traits::*;
+use std::ops::*;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Fixed(i32);
+
+#[auto_ops]
+impl Fixed {
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(Fixed)
+    }
+    fn checked_mul(&self, other: &Self) -> Option<Self> {
+        self.0.checked_mul(other.0).map(Fixed)
+    }
+    fn wrapping_sub(&self, other: &Self) -> Self {
+        Fixed(self.0.wrapping_sub(other.0))
+    }
+}
+
+#[auto_ops]
+impl Sub for Fixed {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Fixed(self.0 - other.0)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Big<T>(T);
+
+#[auto_ops]
+impl<T> CheckedDiv for Big<T>
+where
+    T: CheckedDiv,
+{
+    fn checked_div(&self, other: &Self) -> Option<Self> {
+        self.0.checked_div(&other.0).map(Big)
+    }
+}
+
+#[auto_ops]
+impl MulAddAssign<&Fixed, &Fixed> for Fixed {
+    fn mul_add_assign(&mut self, a: &Fixed, b: &Fixed) {
+        self.0 = self.0 * a.0 + b.0;
+    }
+}
+
+#[auto_ops]
+impl<T> MulAdd<&Big<T>, &Big<T>> for &Big<T>
+where
+    T: Clone + Mul<Output = T> + Add<Output = T>,
+{
+    type Output = Big<T>;
+    fn mul_add(self, a: &Big<T>, b: &Big<T>) -> Self::Output {
+        Big(self.0.clone() * a.0.clone() + b.0.clone())
+    }
+}
+
+fn main() {
+    assert_eq!(Fixed(1) + Fixed(2), Fixed(3));
+    assert_eq!(Fixed(1).wrapping_sub(&Fixed(2)), Fixed(-1));
+    assert_eq!(&Big(6) / Big(3), Big(2));
+    assert_eq!(Fixed(2).mul_add(Fixed(3), &Fixed(4)), Fixed(10));
+    let mut x = Big(2);
+    x.mul_add_assign(Big(3), Big(4));
+    assert_eq!(x, Big(10));
+}
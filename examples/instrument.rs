@@ -0,0 +1,76 @@
+use auto_impl_ops::*;
+use std::any::type_name;
+use std::ops::*;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct V(Vec<i32>);
+
+#[auto_ops]
+impl AddAssign<&V> for V {
+    fn add_assign(&mut self, other: &Self) {
+        for (x, y) in self.0.iter_mut().zip(other.0.iter()) {
+            *x += y;
+        }
+    }
+}
+
+#[auto_ops]
+impl Mul<V> for V {
+    type Output = V;
+    fn mul(self, other: V) -> V {
+        V(self
+            .0
+            .iter()
+            .zip(other.0.iter())
+            .map(|(x, y)| x * y)
+            .collect())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct G<T>(T);
+
+#[auto_ops]
+impl<T> SubAssign<&G<T>> for G<T>
+where
+    T: for<'x> SubAssign<&'x T>,
+{
+    fn sub_assign(&mut self, other: &Self) {
+        self.0 -= &other.0;
+    }
+}
+
+fn find(type_name: &str, variant: &str) -> auto_impl_ops::Snapshot {
+    auto_impl_ops::snapshot()
+        .into_iter()
+        .find(|x| x.type_name == type_name && x.variant == variant)
+        .unwrap()
+}
+
+fn main() {
+    let a = V(vec![1, 2]);
+    let b = V(vec![3, 4]);
+    assert_eq!(&a + &b, V(vec![4, 6]));
+    assert_eq!(&a + &b, V(vec![4, 6]));
+    assert_eq!(a.clone() + b.clone(), V(vec![4, 6]));
+    let add = find(type_name::<V>(), "ref_ref");
+    assert_eq!((add.calls, add.clones, add.defaults), (2, 2, 0));
+    let add = find(type_name::<V>(), "val_val");
+    assert_eq!((add.calls, add.clones, add.defaults), (1, 0, 0));
+
+    let mut c = a.clone();
+    c *= &b;
+    assert_eq!(c, V(vec![3, 8]));
+    let mul = find(type_name::<V>(), "assign_ref");
+    let defaults = if cfg!(feature = "take_mut") { 0 } else { 1 };
+    assert_eq!((mul.calls, mul.clones, mul.defaults), (1, 1, defaults));
+
+    assert_eq!(&G(3) - &G(1), G(2));
+    assert_eq!(&G(3.0) - &G(1.0), G(2.0));
+    assert_eq!(&G(3.0) - &G(1.0), G(2.0));
+    assert_eq!(find(type_name::<G<i32>>(), "ref_ref").calls, 1);
+    assert_eq!(find(type_name::<G<f64>>(), "ref_ref").calls, 2);
+
+    auto_impl_ops::reset();
+    assert_eq!(find(type_name::<V>(), "ref_ref").calls, 0);
+}
@@ -0,0 +1,270 @@
+use super::*;
+use derive_ops::DeriveOp;
+use iter::Identity;
+use syn::parse::{Parse, ParseStream};
+use take::TakeStrategy;
+
+/// Arguments of `#[auto_ops(...)]`.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Args {
+    /// Names of variants to generate. Empty means all variants.
+    pub(crate) variants: Attributes,
+    /// `commutative` or `commutative(T1, T2, ...)`
+    pub(crate) commutative: Option<(Ident, Vec<Type>)>,
+    /// `force_commutative`
+    pub(crate) force_commutative: Option<Ident>,
+    /// `derive_ops(Sub = Add + Neg, ...)`
+    pub(crate) derive_ops: Option<(Ident, Vec<DeriveOp>)>,
+    /// `broadcast(S1, S2, ...)`
+    pub(crate) broadcast: Option<(Ident, Vec<Type>)>,
+    /// `sum` or `sum(identity)`
+    pub(crate) sum: Option<(Ident, Identity)>,
+    /// `product` or `product(identity)`
+    pub(crate) product: Option<(Ident, Identity)>,
+    /// `custom(trait = Pow, method = pow, assign = PowAssign, assign_method = pow_assign)`
+    pub(crate) custom: Option<(Ident, OpFamily)>,
+    /// `lift` or `lift(module)`
+    pub(crate) lift: Option<(Ident, Option<Ident>)>,
+    /// `lazy` or `lazy(module)`
+    pub(crate) lazy: Option<(Ident, Option<Ident>)>,
+    /// `borrow_rhs`
+    pub(crate) borrow_rhs: Option<Ident>,
+    /// `strict`
+    pub(crate) strict: Option<Ident>,
+    /// `allow_clone(variant1, variant2, ...)`
+    pub(crate) allow_clone: Option<(Ident, Vec<Ident>)>,
+    /// `track_caller`
+    pub(crate) track_caller: Option<Ident>,
+    /// `take = default | take_mut | replace_with | placeholder(expr) | clone`
+    pub(crate) take: Option<(Ident, TakeStrategy)>,
+    /// `crate = path`
+    pub(crate) krate: Option<(Ident, Path)>,
+    /// `output_into`
+    pub(crate) output_into: Option<Ident>,
+    /// whether generated methods count calls, which is given by `instrument` feature
+    /// instead of an option
+    pub(crate) instrument: bool,
+}
+
+fn set_once<T>(slot: &mut Option<T>, key: &Ident, value: T) -> Result<()> {
+    if slot.is_some() {
+        return Err(Error::new(key.span(), format!("duplicate option: {}", key)));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+/// Parses `(T1, T2, ...)`.
+fn parse_types(input: ParseStream) -> Result<Vec<Type>> {
+    let content;
+    parenthesized!(content in input);
+    Ok(
+        Punctuated::<Type, token::Comma>::parse_terminated(&content)?
+            .into_iter()
+            .collect(),
+    )
+}
+
+/// Parses optional `(identity)`.
+fn parse_identity(input: ParseStream) -> Result<Identity> {
+    if input.peek(token::Paren) {
+        let content;
+        parenthesized!(content in input);
+        content.parse()
+    } else {
+        Ok(Identity::Default)
+    }
+}
+
+/// Parses `(trait = ..., method = ..., assign = ..., assign_method = ..., output = ...)`.
+fn parse_custom(input: ParseStream) -> Result<OpFamily> {
+    let content;
+    let paren = parenthesized!(content in input);
+    let mut trait_: Option<Path> = None;
+    let mut method: Option<Ident> = None;
+    let mut assign: Option<Path> = None;
+    let mut assign_method: Option<Ident> = None;
+    let mut output: Option<Ident> = None;
+    while !content.is_empty() {
+        let key = content.call(Ident::parse_any)?;
+        content.parse::<token::Eq>()?;
+        match key.to_string().as_str() {
+            "trait" => set_once(&mut trait_, &key, content.parse()?)?,
+            "method" => set_once(&mut method, &key, content.parse()?)?,
+            "assign" => set_once(&mut assign, &key, content.parse()?)?,
+            "assign_method" => set_once(&mut assign_method, &key, content.parse()?)?,
+            "output" => set_once(&mut output, &key, content.parse()?)?,
+            _ => {
+                return Err(Error::new(
+                    key.span(),
+                    format!("unknown key of `custom`: {}", key),
+                ))
+            }
+        }
+        if content.is_empty() {
+            break;
+        }
+        content.parse::<token::Comma>()?;
+    }
+    let required = |key: &str| Error::new(paren.span, format!("`{}` is required", key));
+    let assign = match (assign, assign_method) {
+        (Some(assign), Some(assign_method)) => Some((assign, assign_method)),
+        (None, None) => None,
+        (Some(_), None) => return Err(required("assign_method")),
+        (None, Some(_)) => return Err(required("assign")),
+    };
+    Ok(OpFamily {
+        op: None,
+        trait_: trait_.ok_or_else(|| required("trait"))?,
+        method: method.ok_or_else(|| required("method"))?,
+        assign,
+        output: output.unwrap_or_else(|| format_ident!("Output")),
+    })
+}
+
+/// Parses optional `(module)`.
+fn parse_module(input: ParseStream) -> Result<Option<Ident>> {
+    if input.peek(token::Paren) {
+        let content;
+        parenthesized!(content in input);
+        Ok(Some(content.parse()?))
+    } else {
+        Ok(None)
+    }
+}
+
+impl Parse for Args {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut args = Self::default();
+        while !input.is_empty() {
+            let key = input.call(Ident::parse_any)?;
+            match key.to_string().as_str() {
+                "commutative" => {
+                    let types = if input.peek(token::Paren) {
+                        parse_types(input)?
+                    } else {
+                        Vec::new()
+                    };
+                    set_once(&mut args.commutative, &key, (key.clone(), types))?;
+                }
+                "sum" => {
+                    let identity = parse_identity(input)?;
+                    set_once(&mut args.sum, &key, (key.clone(), identity))?;
+                }
+                "product" => {
+                    let identity = parse_identity(input)?;
+                    set_once(&mut args.product, &key, (key.clone(), identity))?;
+                }
+                "broadcast" => {
+                    let types = parse_types(input)?;
+                    set_once(&mut args.broadcast, &key, (key.clone(), types))?;
+                }
+                "force_commutative" => {
+                    set_once(&mut args.force_commutative, &key, key.clone())?;
+                }
+                "borrow_rhs" => {
+                    set_once(&mut args.borrow_rhs, &key, key.clone())?;
+                }
+                "strict" => {
+                    set_once(&mut args.strict, &key, key.clone())?;
+                }
+                "track_caller" => {
+                    set_once(&mut args.track_caller, &key, key.clone())?;
+                }
+                "output_into" => {
+                    set_once(&mut args.output_into, &key, key.clone())?;
+                }
+                "take" => {
+                    input.parse::<token::Eq>()?;
+                    let strategy = input.parse()?;
+                    set_once(&mut args.take, &key, (key.clone(), strategy))?;
+                }
+                "crate" => {
+                    input.parse::<token::Eq>()?;
+                    let path = input.call(Path::parse_mod_style)?;
+                    set_once(&mut args.krate, &key, (key.clone(), path))?;
+                }
+                "allow_clone" => {
+                    let content;
+                    parenthesized!(content in input);
+                    let list = Punctuated::<Ident, token::Comma>::parse_terminated_with(
+                        &content,
+                        Ident::parse_any,
+                    )?
+                    .into_iter()
+                    .collect();
+                    set_once(&mut args.allow_clone, &key, (key.clone(), list))?;
+                }
+                "derive_ops" => {
+                    let content;
+                    parenthesized!(content in input);
+                    let list = Punctuated::<DeriveOp, token::Comma>::parse_terminated(&content)?
+                        .into_iter()
+                        .collect();
+                    set_once(&mut args.derive_ops, &key, (key.clone(), list))?;
+                }
+                "custom" => {
+                    let family = parse_custom(input)?;
+                    set_once(&mut args.custom, &key, (key.clone(), family))?;
+                }
+                "lift" => {
+                    let module = parse_module(input)?;
+                    set_once(&mut args.lift, &key, (key.clone(), module))?;
+                }
+                "lazy" => {
+                    let module = parse_module(input)?;
+                    set_once(&mut args.lazy, &key, (key.clone(), module))?;
+                }
+                _ => args.variants.push(key),
+            }
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<token::Comma>()?;
+        }
+        if let (None, Some((key, _))) = (&args.strict, &args.allow_clone) {
+            return Err(Error::new(
+                key.span(),
+                format!("`{}` requires `strict`", key),
+            ));
+        }
+        Ok(args)
+    }
+}
+
+impl Args {
+    /// Returns `::auto_impl_ops::__private` or `path::__private` of `crate = path`.
+    pub(crate) fn private_path(&self) -> Path {
+        match &self.krate {
+            Some((_, path)) => parse_quote!(#path::__private),
+            None => parse_quote!(::auto_impl_ops::__private),
+        }
+    }
+    /// Returns an error if an option only meaningful for binary operations is given.
+    pub(crate) fn reject_binary_options(&self) -> Result<()> {
+        let keys = [
+            self.commutative.as_ref().map(|x| &x.0),
+            self.force_commutative.as_ref(),
+            self.derive_ops.as_ref().map(|x| &x.0),
+            self.broadcast.as_ref().map(|x| &x.0),
+            self.sum.as_ref().map(|x| &x.0),
+            self.product.as_ref().map(|x| &x.0),
+            self.custom.as_ref().map(|x| &x.0),
+            self.lift.as_ref().map(|x| &x.0),
+            self.lazy.as_ref().map(|x| &x.0),
+            self.borrow_rhs.as_ref(),
+            self.strict.as_ref(),
+            self.allow_clone.as_ref().map(|x| &x.0),
+            self.track_caller.as_ref(),
+            self.take.as_ref().map(|x| &x.0),
+            self.output_into.as_ref(),
+        ];
+        if let Some(key) = keys.iter().flatten().next() {
+            return Err(Error::new(
+                key.span(),
+                format!("`{}` is not supported for this operation", key),
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,180 @@
+use super::*;
+
+fn collect_idents(tokens: TokenStream, idents: &mut Vec<Ident>) {
+    for token in tokens {
+        match token {
+            proc_macro2::TokenTree::Ident(ident) => idents.push(ident),
+            proc_macro2::TokenTree::Group(group) => collect_idents(group.stream(), idents),
+            _ => (),
+        }
+    }
+}
+
+/// Returns a type parameter which appears in `rhs_type` but not in `self_type`,
+/// which is unconstrained in `impl<X: Borrow<U>> Op<X> for T`.
+fn find_unconstrained<'a>(
+    generics: &'a Generics,
+    self_type: &Type,
+    rhs_type: &Type,
+) -> Option<&'a Ident> {
+    let mut self_idents = Vec::new();
+    collect_idents(self_type.to_token_stream(), &mut self_idents);
+    let mut rhs_idents = Vec::new();
+    collect_idents(rhs_type.to_token_stream(), &mut rhs_idents);
+    generics
+        .type_params()
+        .map(|x| &x.ident)
+        .find(|x| rhs_idents.contains(x) && !self_idents.contains(x))
+}
+
+/// Makes `impl<X: Borrow<U>> Op<X> for T` from `impl Op<&U> for T`.
+fn gen_source(implement: &ItemImpl, family: &OpFamily, is_assign: bool) -> Result<ItemImpl> {
+    let last_segment = get_last_segment(implement)?;
+    let rhs_type = get_rhs_type(&last_segment.arguments, &implement.self_ty)?;
+    let rr_rhs_type = remove_reference(rhs_type);
+    let mut work = implement.clone();
+    let x = fresh_type_param(&work.generics, "X");
+    work.generics
+        .params
+        .push(parse_quote!(#x: ::core::borrow::Borrow<#rr_rhs_type>));
+    family.set_trait(&mut work, is_assign, &parse_quote!(#x));
+    let mut found = false;
+    for item in work.items.iter_mut() {
+        if let ImplItem::Method(method) = item {
+            let span = method.sig.inputs.span();
+            let arg = match method.sig.inputs.iter_mut().nth(1) {
+                Some(FnArg::Typed(arg)) => arg,
+                _ => return Err(Error::new(span, "Expected RHS argument")),
+            };
+            let pat = arg.pat.as_ref().clone();
+            let rhs = local("rhs");
+            *arg.pat = parse_quote!(#rhs);
+            *arg.ty = parse_quote!(#x);
+            method.block.stmts.insert(
+                0,
+                parse_quote! {
+                    let #pat: &#rr_rhs_type = ::core::borrow::Borrow::borrow(&#rhs);
+                },
+            );
+            found = true;
+        }
+    }
+    if !found {
+        return Err(Error::new(implement.span(), "Expected method"));
+    }
+    Ok(work)
+}
+
+/// Makes a hidden associated constant of `T` named after `key` and the operation,
+/// so that another `borrow_rhs` impl of the same operation of `T`, which overlaps,
+/// is reported as a duplicate definition at `key`.
+fn gen_marker(key: &Ident, implement: &ItemImpl, family: &OpFamily) -> ItemImpl {
+    let name = format!("{}_{}", key, family.trait_.segments.last().unwrap().ident);
+    let marker = Ident::new(&name, key.span());
+    let mut work = implement.clone();
+    work.trait_ = None;
+    *work.self_ty = remove_reference(&implement.self_ty).clone();
+    work.items = vec![parse_quote! {
+        #[doc(hidden)]
+        #[allow(dead_code, non_upper_case_globals)]
+        const #marker: () = ();
+    }];
+    work
+}
+
+/// Generates `impl<X: Borrow<U>> Op<X>` for `T`, `&T` and the assign operation
+/// instead of the six variants.
+pub(crate) fn generate_borrow_rhs(
+    key: &Ident,
+    args: &Args,
+    generator: &Generator,
+) -> Result<TokenStream> {
+    let conflicts = [
+        args.commutative.as_ref().map(|x| &x.0),
+        args.broadcast.as_ref().map(|x| &x.0),
+        args.lazy.as_ref().map(|x| &x.0),
+    ];
+    if let Some(conflict) = conflicts.iter().flatten().next() {
+        return Err(Error::new(
+            conflict.span(),
+            format!(
+                "`{}` cannot be used with `{}` because their impls overlap",
+                conflict, key
+            ),
+        ));
+    }
+    let implement = generator.implement;
+    let source_op = generator.source_op;
+    if !source_op.2 {
+        return Err(Error::new(
+            generator.rhs_type.span(),
+            format!("`{}` requires that the type of RHS is a reference", key),
+        ));
+    }
+    if let Some(param) =
+        find_unconstrained(&implement.generics, generator.self_type, generator.rhs_type)
+    {
+        return Err(Error::new(
+            param.span(),
+            format!(
+                "`{}` makes the type parameter `{}` unconstrained because it appears only in RHS",
+                key, param
+            ),
+        ));
+    }
+    let work = gen_source(implement, generator.family, source_op.0)?;
+    let last_segment = get_last_segment(&work)?;
+    let generator = Generator {
+        implement: &work,
+        family: generator.family,
+        source_op: Operate(source_op.0, source_op.1, false),
+        self_type: &work.self_ty,
+        rhs_type: get_rhs_type(&last_segment.arguments, &work.self_ty)?,
+        commutative: false,
+        track_caller: generator.track_caller,
+        take: generator.take,
+        private: generator.private,
+        other_output: generator.other_output.clone(),
+        output_into: generator.output_into,
+        instrument: generator.instrument,
+    };
+    // Each impl covers both variants of the standard names because `X` may be `U` or `&U`.
+    let mut list = vec![
+        (["ref_ref", "ref_val"], Operate(false, true, false)),
+        (["val_ref", "val_val"], Operate(false, false, false)),
+    ];
+    if generator.family.assign.is_some() {
+        list.insert(
+            0,
+            (["assign_ref", "assign_val"], Operate(true, false, false)),
+        );
+    }
+    let mut result = TokenStream::new();
+    if generator.family.op.is_some() {
+        let marker = gen_marker(key, implement, generator.family);
+        result.extend(quote!(#marker));
+    }
+    for (names, op) in list {
+        let requested = args
+            .variants
+            .iter()
+            .find(|x| names.iter().any(|name| x == name));
+        if args.variants.is_empty() || op == generator.source_op || requested.is_some() {
+            if op.0 && !generator.assign_available() {
+                match requested {
+                    Some(key) => return Err(generator.output_error(key)),
+                    None => continue,
+                }
+            }
+            if op != generator.source_op
+                && names
+                    .iter()
+                    .all(|name| generator.check_strict(args, name, op).is_err())
+            {
+                generator.check_strict(args, names[0], op)?;
+            }
+            result.extend(generator.generate(op)?);
+        }
+    }
+    Ok(result)
+}
@@ -0,0 +1,146 @@
+use super::*;
+
+/// Replaces `rhs.field` and `rhs.field[i]` with `(*rhs)`,
+/// and `xs.zip(rhs.field.iter())` with `xs.zip(::core::iter::repeat(rhs))`.
+/// Other uses of `rhs`, such as `rhs.a.b`, `rhs.a.len()` or `rhs` itself, are errors
+/// because they cannot be replaced with the scalar.
+struct ReplaceField<'a> {
+    rhs: &'a Ident,
+    error: Option<Error>,
+}
+impl ReplaceField<'_> {
+    fn is_rhs(&self, expr: &Expr) -> bool {
+        matches!(expr, Expr::Path(ExprPath { qself: None, path, .. }) if path.is_ident(self.rhs))
+    }
+    fn is_rhs_field(&self, expr: &Expr) -> bool {
+        matches!(expr, Expr::Field(ExprField { base, .. }) if self.is_rhs(base))
+    }
+    /// Returns whether `expr` is `rhs.field.iter()` or `rhs.field.into_iter()`.
+    fn is_rhs_iter(&self, expr: &Expr) -> bool {
+        matches!(
+            expr,
+            Expr::MethodCall(ExprMethodCall { receiver, method, args, .. })
+                if (method == "iter" || method == "into_iter")
+                    && args.is_empty()
+                    && self.is_rhs_field(receiver)
+        )
+    }
+}
+impl VisitMut for ReplaceField<'_> {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        let rhs = self.rhs;
+        let nested = match expr {
+            Expr::Field(ExprField { base, .. }) if self.is_rhs(base) => {
+                *expr = parse_quote!((*#rhs));
+                return;
+            }
+            Expr::Index(ExprIndex { expr: base, .. }) if self.is_rhs_field(base) => {
+                *expr = parse_quote!((*#rhs));
+                return;
+            }
+            Expr::MethodCall(ExprMethodCall {
+                receiver,
+                method,
+                args,
+                ..
+            }) if method == "zip" && args.len() == 1 && self.is_rhs_iter(&args[0]) => {
+                args[0] = parse_quote!(::core::iter::repeat(#rhs));
+                self.visit_expr_mut(receiver);
+                return;
+            }
+            Expr::Field(ExprField { base, .. })
+            | Expr::MethodCall(ExprMethodCall { receiver: base, .. })
+            | Expr::Index(ExprIndex { expr: base, .. }) => self.is_rhs_field(base),
+            _ => self.is_rhs(expr),
+        };
+        if nested {
+            if self.error.is_none() {
+                self.error = Some(Error::new(
+                    expr.span(),
+                    format!(
+                        "`broadcast` requires that `{0}` is used only as `{0}.field`, `{0}.field[i]` or `xs.zip({0}.field.iter())`, which are replaced with the scalar",
+                        rhs
+                    ),
+                ));
+            }
+            return;
+        }
+        visit_mut::visit_expr_mut(self, expr);
+    }
+}
+
+fn get_rhs_arg(method: &mut ImplItemMethod) -> Result<(Ident, &mut Box<Type>)> {
+    let span = method.sig.inputs.span();
+    if let Some(FnArg::Typed(PatType { pat, ty, .. })) = method.sig.inputs.iter_mut().nth(1) {
+        if let Pat::Ident(PatIdent { ident, .. }) = pat.as_ref() {
+            return Ok((ident.clone(), ty));
+        }
+        return Err(Error::new(
+            pat.span(),
+            "`broadcast` requires an identifier pattern",
+        ));
+    }
+    Err(Error::new(span, "Expected RHS argument"))
+}
+
+/// Makes the scalar source impl `T @= &S` from the element-wise source impl `T @= &T`.
+fn gen_source(implement: &ItemImpl, rhs_type: &Type, scalar_type: &Type) -> Result<ItemImpl> {
+    let mut work = implement.clone();
+    let scalar_rhs_type = copy_reference(scalar_type, rhs_type);
+    let last_segment = work.trait_.as_mut().unwrap().1.segments.last_mut().unwrap();
+    let trait_ = &last_segment.ident;
+    *last_segment = parse_quote! { #trait_<#scalar_rhs_type> };
+    let mut found = false;
+    for item in work.items.iter_mut() {
+        if let ImplItem::Method(method) = item {
+            let (rhs, ty) = get_rhs_arg(method)?;
+            **ty = scalar_rhs_type.clone();
+            let mut replace = ReplaceField {
+                rhs: &rhs,
+                error: None,
+            };
+            replace.visit_block_mut(&mut method.block);
+            if let Some(error) = replace.error {
+                return Err(error);
+            }
+            found = true;
+        }
+    }
+    if !found {
+        return Err(Error::new(implement.span(), "Expected method"));
+    }
+    Ok(work)
+}
+
+/// Generates variants of `T @ S` for each scalar type `S`.
+pub(crate) fn generate_broadcast(
+    args: &Args,
+    implement: &ItemImpl,
+    source_op: Operate,
+    rhs_type: &Type,
+    scalar_types: &[Type],
+) -> Result<TokenStream> {
+    if !source_op.2 {
+        return Err(Error::new(
+            rhs_type.span(),
+            "`broadcast` requires that the type of RHS is a reference",
+        ));
+    }
+    let mut result = TokenStream::new();
+    for scalar_type in scalar_types {
+        let source = gen_source(implement, rhs_type, scalar_type)?;
+        let args = Args {
+            variants: args.variants.clone(),
+            strict: args.strict.clone(),
+            allow_clone: args.allow_clone.clone(),
+            track_caller: args.track_caller.clone(),
+            take: args.take.clone(),
+            krate: args.krate.clone(),
+            output_into: args.output_into.clone(),
+            instrument: args.instrument,
+            ..Args::default()
+        };
+        result.extend(auto_ops_generate(args, source)?);
+    }
+    Ok(result)
+}
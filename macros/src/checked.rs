@@ -0,0 +1,249 @@
+use super::*;
+use proc_macro2::{Group, TokenTree};
+
+#[derive(Clone, Copy, Debug, Display, EnumString, PartialEq, Eq, Hash)]
+pub(crate) enum CheckedOpTrait {
+    CheckedAdd,
+    CheckedSub,
+    CheckedMul,
+    CheckedDiv,
+    CheckedRem,
+    WrappingAdd,
+    WrappingSub,
+    WrappingMul,
+    SaturatingAdd,
+    SaturatingSub,
+    SaturatingMul,
+}
+impl ToTokens for CheckedOpTrait {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(self.to_path(Span::call_site()).into_token_stream());
+    }
+}
+impl CheckedOpTrait {
+    const ALL: [Self; 11] = {
+        use CheckedOpTrait::*;
+        [
+            CheckedAdd,
+            CheckedSub,
+            CheckedMul,
+            CheckedDiv,
+            CheckedRem,
+            WrappingAdd,
+            WrappingSub,
+            WrappingMul,
+            SaturatingAdd,
+            SaturatingSub,
+            SaturatingMul,
+        ]
+    };
+    /// Returns `::num_traits::CheckedAdd` etc. with `span`.
+    fn to_path(self, span: Span) -> Path {
+        let ident = Ident::new(&self.to_string(), span);
+        parse_quote_spanned!(span=> ::num_traits::#ident)
+    }
+    fn to_func_ident(self) -> Ident {
+        use CheckedOpTrait::*;
+        match self {
+            CheckedAdd => format_ident!("checked_add"),
+            CheckedSub => format_ident!("checked_sub"),
+            CheckedMul => format_ident!("checked_mul"),
+            CheckedDiv => format_ident!("checked_div"),
+            CheckedRem => format_ident!("checked_rem"),
+            WrappingAdd => format_ident!("wrapping_add"),
+            WrappingSub => format_ident!("wrapping_sub"),
+            WrappingMul => format_ident!("wrapping_mul"),
+            SaturatingAdd => format_ident!("saturating_add"),
+            SaturatingSub => format_ident!("saturating_sub"),
+            SaturatingMul => format_ident!("saturating_mul"),
+        }
+    }
+    fn to_op(self) -> OpTrait {
+        use CheckedOpTrait::*;
+        match self {
+            CheckedAdd | WrappingAdd | SaturatingAdd => OpTrait::Add,
+            CheckedSub | WrappingSub | SaturatingSub => OpTrait::Sub,
+            CheckedMul | WrappingMul | SaturatingMul => OpTrait::Mul,
+            CheckedDiv => OpTrait::Div,
+            CheckedRem => OpTrait::Rem,
+        }
+    }
+    /// Returns the panic message of `std::ops` operation if `self` is a checked operation.
+    fn overflow_message(self) -> Option<&'static str> {
+        use CheckedOpTrait::*;
+        match self {
+            CheckedAdd => Some("attempt to add with overflow"),
+            CheckedSub => Some("attempt to subtract with overflow"),
+            CheckedMul => Some("attempt to multiply with overflow"),
+            CheckedDiv => Some("attempt to divide by zero or with overflow"),
+            CheckedRem => {
+                Some("attempt to calculate the remainder with a divisor of zero or with overflow")
+            }
+            _ => None,
+        }
+    }
+    fn output_type(self) -> Type {
+        if self.overflow_message().is_some() {
+            parse_quote!(::core::option::Option<Self>)
+        } else {
+            parse_quote!(Self)
+        }
+    }
+}
+
+/// Returns `#[track_caller]` if `track_caller` is true.
+fn gen_track_caller(track_caller: bool) -> TokenStream {
+    if track_caller {
+        quote!(#[track_caller])
+    } else {
+        TokenStream::new()
+    }
+}
+
+/// Makes `&T @ &T` source impl which panics if the checked operation returns `None`.
+/// The source method has `#[track_caller]` if `track_caller` is true,
+/// so that the panic is reported at the caller.
+fn gen_std_source(
+    implement: &ItemImpl,
+    op: CheckedOpTrait,
+    track_caller: bool,
+) -> Option<ItemImpl> {
+    let message = op.overflow_message()?;
+    let track_caller = gen_track_caller(track_caller);
+    let self_type = &implement.self_ty;
+    let (impl_generics, _, where_clause) = implement.generics.split_for_impl();
+    let trait_ = op.to_op();
+    let fn_name = trait_.to_func_ident();
+    let checked_fn_name = op.to_func_ident();
+    Some(parse_quote! {
+        impl #impl_generics #trait_<&#self_type> for &#self_type #where_clause {
+            type Output = #self_type;
+            #track_caller
+            fn #fn_name(self, rhs: &#self_type) -> Self::Output {
+                #op::#checked_fn_name(self, rhs).expect(#message)
+            }
+        }
+    })
+}
+
+fn gen_std_ops(
+    args: &Args,
+    implement: &ItemImpl,
+    op: CheckedOpTrait,
+    track_caller: bool,
+) -> Result<TokenStream> {
+    if let Some(source) = gen_std_source(implement, op, track_caller) {
+        let args = Args {
+            variants: args.variants.clone(),
+            krate: args.krate.clone(),
+            instrument: args.instrument,
+            ..Args::default()
+        };
+        auto_ops_generate(args, source)
+    } else {
+        Ok(TokenStream::new())
+    }
+}
+
+/// Generates `std::ops` operations from `impl CheckedAdd for T`.
+pub(crate) fn auto_ops_generate_checked(
+    args: &Args,
+    implement: &ItemImpl,
+    op: CheckedOpTrait,
+) -> Result<TokenStream> {
+    if op.overflow_message().is_none() {
+        return Err(Error::new(
+            implement.span(),
+            format!(
+                "There is nothing to generate from `{0}`, use `#[auto_ops]` on inherent `{1}` instead",
+                op,
+                op.to_func_ident()
+            ),
+        ));
+    }
+    if is_ref(&implement.self_ty) {
+        return Err(Error::new(
+            implement.self_ty.span(),
+            "Type of `Self` must not reference",
+        ));
+    }
+    let mut result = implement.to_token_stream();
+    result.extend(gen_std_ops(
+        args,
+        implement,
+        op,
+        has_track_caller(implement),
+    )?);
+    Ok(result)
+}
+
+/// Sets `span` to all tokens of `tokens`.
+fn respan(tokens: TokenStream, span: Span) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            if let TokenTree::Group(group) = &token {
+                let mut new = Group::new(group.delimiter(), respan(group.stream(), span));
+                new.set_span(span);
+                token = TokenTree::Group(new);
+            } else {
+                token.set_span(span);
+            }
+            token
+        })
+        .collect()
+}
+
+/// Generates `num_traits` impls and `std::ops` operations from inherent methods
+/// such as `fn checked_add(&self, rhs: &Self) -> Option<Self>`.
+/// `#[auto_ops(track_caller)]` adds `#[track_caller]` to these methods and all generated methods.
+pub(crate) fn auto_ops_generate_inherent(args: &Args, implement: &ItemImpl) -> Result<TokenStream> {
+    let mut implement = implement.clone();
+    let mut ops = Vec::new();
+    for item in implement.items.iter_mut() {
+        let method = if let ImplItem::Method(method) = item {
+            method
+        } else {
+            continue;
+        };
+        let op = match CheckedOpTrait::ALL
+            .iter()
+            .find(|x| method.sig.ident == x.to_func_ident())
+        {
+            Some(op) => *op,
+            None => continue,
+        };
+        if args.track_caller.is_some() && !is_track_caller(&method.attrs) {
+            method.attrs.push(parse_quote!(#[track_caller]));
+        }
+        ops.push((op, method.sig.ident.span(), is_track_caller(&method.attrs)));
+    }
+    let implement = &implement;
+    let mut result = implement.to_token_stream();
+    for &(op, span, track_caller) in ops.iter() {
+        let mut work = implement.clone();
+        // `WrappingAdd` and `SaturatingAdd` require `Add<Output = Self>`, which is generated only
+        // from checked methods, so a missing `Add` is reported at the method.
+        work.trait_ = Some((None, op.to_path(span), Default::default()));
+        *work.self_ty = parse2(respan(implement.self_ty.to_token_stream(), span))?;
+        work.items.clear();
+        let fn_name = op.to_func_ident();
+        let output_type = op.output_type();
+        let track_caller_attr = gen_track_caller(track_caller);
+        work.items.push(parse_quote! {
+            #track_caller_attr
+            fn #fn_name(&self, v: &Self) -> #output_type {
+                Self::#fn_name(self, v)
+            }
+        });
+        result.extend(quote!(#work));
+        result.extend(gen_std_ops(args, implement, op, track_caller)?);
+    }
+    if ops.is_empty() {
+        return Err(Error::new(
+            implement.span(),
+            "Checked, wrapping or saturating method is not found",
+        ));
+    }
+    Ok(result)
+}
@@ -0,0 +1,134 @@
+use super::*;
+
+#[derive(Clone, Copy, Debug, Display, EnumString, PartialEq, Eq, Hash)]
+pub(crate) enum CmpTrait {
+    PartialEq,
+    PartialOrd,
+}
+impl ToTokens for CmpTrait {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ident = Ident::new(&self.to_string(), Span::call_site());
+        tokens.extend(quote!(::core::cmp::#ident));
+    }
+}
+impl CmpTrait {
+    fn to_func_ident(self) -> Ident {
+        match self {
+            Self::PartialEq => format_ident!("eq"),
+            Self::PartialOrd => format_ident!("partial_cmp"),
+        }
+    }
+    fn output_type(self) -> Type {
+        match self {
+            Self::PartialEq => parse_quote!(bool),
+            Self::PartialOrd => parse_quote!(::core::option::Option<::core::cmp::Ordering>),
+        }
+    }
+}
+
+/// `CmpOperate(is_reversed, is_lhs_ref, is_rhs_ref)`
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+struct CmpOperate(bool, bool, bool);
+
+struct CmpGenerator<'a> {
+    implement: &'a ItemImpl,
+    trait_: CmpTrait,
+    self_type: &'a Type,
+    rhs_type: &'a Type,
+}
+impl CmpGenerator<'_> {
+    fn generate(&self, op: CmpOperate) -> TokenStream {
+        let mut work = self.implement.clone();
+        let (lhs_type, rhs_type) = if op.0 {
+            (self.rhs_type, self.self_type)
+        } else {
+            (self.self_type, self.rhs_type)
+        };
+        let lhs_type = Generator::get_arg_type(op.1, lhs_type);
+        let rhs_type = Generator::get_arg_type(op.2, rhs_type);
+        let trait_ = self.trait_;
+        work.trait_.as_mut().unwrap().1 = parse_quote! { #trait_<#rhs_type> };
+        *work.self_ty.as_mut() = lhs_type;
+        work.items.clear();
+        let lhs = if op.1 { quote!(*self) } else { quote!(self) };
+        let rhs = if op.2 { quote!(*other) } else { quote!(other) };
+        let fn_name = trait_.to_func_ident();
+        let output_type = trait_.output_type();
+        let body = match (op.0, trait_) {
+            (false, _) => quote!(#trait_::#fn_name(#lhs, #rhs)),
+            (true, CmpTrait::PartialEq) => quote!(#trait_::#fn_name(#rhs, #lhs)),
+            (true, CmpTrait::PartialOrd) => {
+                quote!(#trait_::#fn_name(#rhs, #lhs).map(::core::cmp::Ordering::reverse))
+            }
+        };
+        work.items.push(parse_quote! {
+            fn #fn_name(&self, other: &#rhs_type) -> #output_type {
+                #body
+            }
+        });
+        drop_unused_lifetimes(&mut work);
+        quote!(#work)
+    }
+}
+
+/// Generates `PartialEq<&U> for T`, `PartialEq<U> for &T` and the mirrored impls
+/// from `impl PartialEq<U> for T`, and `PartialOrd` in the same way.
+///
+/// `&T == &U` is not generated because `std` already implements it.
+pub(crate) fn auto_ops_generate_cmp(
+    attrs: &Attributes,
+    implement: &ItemImpl,
+    trait_: CmpTrait,
+) -> Result<TokenStream> {
+    let last_segment = get_last_segment(implement)?;
+    let self_type = implement.self_ty.as_ref();
+    let rhs_type = get_rhs_type(&last_segment.arguments, self_type)?;
+    if is_ref(self_type) || is_ref(rhs_type) {
+        return Err(Error::new(
+            implement.span(),
+            format!(
+                "`{}` requires that the types of LHS and RHS are not references",
+                trait_
+            ),
+        ));
+    }
+    if get_type_param(rhs_type, &implement.generics).is_some() {
+        return Err(Error::new(
+            rhs_type.span(),
+            format!(
+                "`{}` requires that the type of RHS is not a type parameter",
+                trait_
+            ),
+        ));
+    }
+    let generator = CmpGenerator {
+        implement,
+        trait_,
+        self_type,
+        rhs_type,
+    };
+    let mut list = vec![
+        ("val_ref", CmpOperate(false, false, true)),
+        ("ref_val", CmpOperate(false, true, false)),
+    ];
+    if self_type != rhs_type {
+        list.extend([
+            ("rev_val_val", CmpOperate(true, false, false)),
+            ("rev_val_ref", CmpOperate(true, false, true)),
+            ("rev_ref_val", CmpOperate(true, true, false)),
+        ]);
+    }
+    let map = list.iter().copied().collect::<HashMap<_, _>>();
+    let mut attrs = attrs.clone();
+    if attrs.is_empty() {
+        attrs = list.iter().map(|(x, _)| format_ident!("{}", x)).collect();
+    }
+    let mut result = implement.to_token_stream();
+    for i in attrs.iter() {
+        let s = i.to_string();
+        if let Some(op) = map.get(s.as_str()) {
+            result.extend(generator.generate(*op));
+        }
+    }
+    Ok(result)
+}
@@ -0,0 +1,156 @@
+use super::*;
+
+fn is_phantom_data(type_: &Type) -> bool {
+    if let Type::Path(TypePath { path, .. }) = type_ {
+        path.segments.last().unwrap().ident == "PhantomData"
+    } else {
+        false
+    }
+}
+
+/// Parses `#[ops(...)]` attributes.
+fn parse_ops_attrs(attrs: &[Attribute]) -> Result<Vec<Punctuated<Ident, token::Comma>>> {
+    attrs
+        .iter()
+        .filter(|x| x.path.is_ident("ops"))
+        .map(|x| x.parse_args_with(Punctuated::parse_terminated))
+        .collect()
+}
+
+fn parse_ops(list: &Punctuated<Ident, token::Comma>) -> Result<Vec<OpTrait>> {
+    list.iter()
+        .map(|x| Ok(OpTrait::try_from(x.clone())?.to_assign()))
+        .collect()
+}
+
+#[derive(Clone, Debug)]
+struct OpsField<'a> {
+    member: Member,
+    type_: &'a Type,
+    skip: Vec<OpTrait>,
+    skip_all: bool,
+}
+impl<'a> OpsField<'a> {
+    fn new(index: usize, field: &'a Field) -> Result<Self> {
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(index.into()),
+        };
+        let mut skip = Vec::new();
+        let mut skip_all = is_phantom_data(&field.ty);
+        for attr in field.attrs.iter().filter(|x| x.path.is_ident("ops")) {
+            let meta = attr.parse_args::<Meta>()?;
+            match meta {
+                Meta::Path(path) if path.is_ident("skip") => skip_all = true,
+                Meta::List(list) if list.path.is_ident("skip") => {
+                    for nested in list.nested.iter() {
+                        match nested {
+                            NestedMeta::Meta(Meta::Path(path)) if path.get_ident().is_some() => {
+                                let ident = path.get_ident().unwrap().clone();
+                                skip.push(OpTrait::try_from(ident)?.to_assign());
+                            }
+                            _ => return Err(Error::new(nested.span(), "Expected operation name")),
+                        }
+                    }
+                }
+                _ => return Err(Error::new(meta.span(), "Expected `skip` or `skip(...)`")),
+            }
+        }
+        Ok(Self {
+            member,
+            type_: &field.ty,
+            skip,
+            skip_all,
+        })
+    }
+    fn is_target(&self, op: OpTrait) -> bool {
+        !self.skip_all && !self.skip.contains(&op)
+    }
+}
+
+fn gen_source(input: &DeriveInput, fields: &[OpsField], op: OpTrait) -> ItemImpl {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut implement: ItemImpl = parse_quote! {
+        impl #impl_generics #op<&#name #ty_generics> for #name #ty_generics #where_clause {}
+    };
+    let fn_name = op.to_func_ident();
+    let mut types = Vec::<&Type>::new();
+    let mut stmts = Vec::<Stmt>::new();
+    for field in fields.iter().filter(|x| x.is_target(op)) {
+        if !types.contains(&field.type_) {
+            types.push(field.type_);
+        }
+        let member = &field.member;
+        stmts.push(parse_quote! {
+            #op::#fn_name(&mut self.#member, &rhs.#member);
+        });
+    }
+    if !types.is_empty() {
+        let wc = implement.generics.make_where_clause();
+        for type_ in types {
+            wc.predicates.push(parse_quote! {
+                #type_: for<'x> #op<&'x #type_>
+            });
+        }
+    }
+    implement.items.push(parse_quote! {
+        fn #fn_name(&mut self, rhs: &Self) {
+            #(#stmts)*
+        }
+    });
+    implement
+}
+
+fn derive_auto_ops_inner(tokens: TokenStream, instrument: bool) -> Result<TokenStream> {
+    let input: DeriveInput = parse2(tokens)?;
+    let data = match &input.data {
+        Data::Struct(data) => data,
+        Data::Enum(data) => {
+            return Err(Error::new(
+                data.enum_token.span(),
+                "`AutoOps` cannot be derived for enums",
+            ))
+        }
+        Data::Union(data) => {
+            return Err(Error::new(
+                data.union_token.span(),
+                "`AutoOps` cannot be derived for unions",
+            ))
+        }
+    };
+    let fields = data
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(i, x)| OpsField::new(i, x))
+        .collect::<Result<Vec<_>>>()?;
+    let mut ops = Vec::new();
+    for list in parse_ops_attrs(&input.attrs)? {
+        for op in parse_ops(&list)? {
+            if !ops.contains(&op) {
+                ops.push(op);
+            }
+        }
+    }
+    if ops.is_empty() {
+        return Err(Error::new(
+            input.ident.span(),
+            "`#[ops(...)]` is required to derive `AutoOps`",
+        ));
+    }
+    let mut result = TokenStream::new();
+    for op in ops {
+        let implement = gen_source(&input, &fields, op);
+        let args = Args {
+            instrument,
+            ..Args::default()
+        };
+        result.extend(auto_ops_generate(args, implement)?);
+    }
+    Ok(result)
+}
+
+pub(crate) fn derive_auto_ops_impl(tokens: TokenStream, instrument: bool) -> TokenStream {
+    derive_auto_ops_inner(tokens, instrument).unwrap_or_else(Error::into_compile_error)
+}
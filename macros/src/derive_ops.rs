@@ -0,0 +1,117 @@
+use super::*;
+use syn::parse::{Parse, ParseStream};
+
+/// `Target = Base + Unary` in `derive_ops(...)`, which means `a Target b` is `a Base (Unary b)`.
+#[derive(Clone, Debug)]
+pub(crate) struct DeriveOp {
+    span: Span,
+    target: OpTrait,
+    base: OpTrait,
+    unary: UnaryOpTrait,
+}
+impl Parse for DeriveOp {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let target: Ident = input.parse()?;
+        let span = target.span();
+        input.parse::<token::Eq>()?;
+        let base: Ident = input.parse()?;
+        input.parse::<token::Add>()?;
+        let unary: Ident = input.parse()?;
+        let unary = UnaryOpTrait::from_str(&unary.to_string()).map_err(|_| {
+            Error::new(unary.span(), format!("Expected unary operation: {}", unary))
+        })?;
+        Ok(Self {
+            span,
+            target: OpTrait::try_from(target)?.to_assign(),
+            base: OpTrait::try_from(base)?.to_assign(),
+            unary,
+        })
+    }
+}
+
+impl DeriveOp {
+    /// Returns whether `Base` is `op`, i.e. the derived operation calls `T @= &U` of the source.
+    pub(crate) fn is_based_on(&self, op: OpTrait) -> bool {
+        self.base == op.to_assign()
+    }
+    /// Makes the `T @= &U` source impl of the derived operation.
+    fn gen_source(&self, implement: &ItemImpl, source_op: OpTrait, rhs_type: &Type) -> ItemImpl {
+        let self_type = remove_reference(&implement.self_ty);
+        let rr_rhs_type = remove_reference(rhs_type);
+        let rhs_type = Generator::get_arg_type(true, rhs_type);
+        let DeriveOp {
+            target,
+            base,
+            unary,
+            ..
+        } = self;
+        let mut work = implement.clone();
+        work.trait_.as_mut().unwrap().1 = parse_quote! { #target<#rhs_type> };
+        *work.self_ty.as_mut() = self_type.clone();
+        let wc = work.generics.make_where_clause();
+        wc.predicates.push(parse_quote! {
+            for<'x> &'x #rr_rhs_type: #unary<Output = #rr_rhs_type>
+        });
+        if !self.is_based_on(source_op) {
+            wc.predicates.push(parse_quote! {
+                #self_type: for<'x> #base<&'x #rr_rhs_type>
+            });
+        }
+        work.items.clear();
+        let fn_name = target.to_func_ident();
+        let base_fn_name = base.to_func_ident();
+        let unary_fn_name = unary.to_func_ident();
+        let rhs = local("rhs");
+        let track_caller = if has_track_caller(implement) {
+            quote!(#[track_caller])
+        } else {
+            TokenStream::new()
+        };
+        work.items.push(parse_quote! {
+            #track_caller
+            fn #fn_name(&mut self, #rhs: #rhs_type) {
+                <#self_type as #base<&#rr_rhs_type>>::#base_fn_name(
+                    self,
+                    &<&#rr_rhs_type as #unary>::#unary_fn_name(#rhs),
+                );
+            }
+        });
+        work
+    }
+}
+
+/// Generates all variants of the operations listed in `derive_ops(...)`.
+pub(crate) fn generate_derived(
+    key: &Ident,
+    args: &Args,
+    derive_ops: &[DeriveOp],
+    generator: &Generator,
+    source_op: OpTrait,
+) -> Result<TokenStream> {
+    let mut result = TokenStream::new();
+    for derive_op in derive_ops {
+        if derive_op.target == source_op.to_assign() {
+            return Err(Error::new(
+                derive_op.span,
+                format!("`{}` is already implemented", source_op.to_non_assign()),
+            ));
+        }
+        if derive_op.is_based_on(source_op) && !generator.assign_available() {
+            return Err(generator.output_error(key));
+        }
+        let source = derive_op.gen_source(generator.implement, source_op, generator.rhs_type);
+        let args = Args {
+            variants: args.variants.clone(),
+            strict: args.strict.clone(),
+            allow_clone: args.allow_clone.clone(),
+            track_caller: args.track_caller.clone(),
+            take: args.take.clone(),
+            krate: args.krate.clone(),
+            output_into: args.output_into.clone(),
+            instrument: args.instrument,
+            ..Args::default()
+        };
+        result.extend(auto_ops_generate(args, source)?);
+    }
+    Ok(result)
+}
@@ -0,0 +1,153 @@
+use super::*;
+use syn::parse::{Parse, ParseStream};
+
+/// Initial value of `Sum` or `Product`.
+#[derive(Clone, Debug)]
+pub(crate) enum Identity {
+    Default,
+    Zero,
+    One,
+    Expr(Box<Expr>),
+}
+impl Parse for Identity {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let expr: Expr = input.parse()?;
+        if let Expr::Path(ExprPath {
+            qself: None, path, ..
+        }) = &expr
+        {
+            if path.is_ident("default") {
+                return Ok(Self::Default);
+            } else if path.is_ident("zero") {
+                return Ok(Self::Zero);
+            } else if path.is_ident("one") {
+                return Ok(Self::One);
+            }
+        }
+        Ok(Self::Expr(Box::new(expr)))
+    }
+}
+impl Identity {
+    fn to_expr(&self) -> Expr {
+        match self {
+            Self::Default => parse_quote!(<Self as ::core::default::Default>::default()),
+            Self::Zero => parse_quote!(<Self as ::num_traits::Zero>::zero()),
+            Self::One => parse_quote!(<Self as ::num_traits::One>::one()),
+            Self::Expr(expr) => expr.as_ref().clone(),
+        }
+    }
+    fn update_where_clause(&self, generics: &mut Generics, self_type: &Type) {
+        let bound: Option<Path> = match self {
+            Self::Default => Some(parse_quote!(::core::default::Default)),
+            Self::Zero => Some(parse_quote!(::num_traits::Zero)),
+            Self::One => Some(parse_quote!(::num_traits::One)),
+            Self::Expr(_) => None,
+        };
+        if let Some(bound) = bound {
+            let wc = generics.make_where_clause();
+            wc.predicates.push(parse_quote! {
+                #self_type: #bound
+            });
+        }
+    }
+}
+
+/// `Sum` or `Product`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum IterTrait {
+    Sum,
+    Product,
+}
+impl IterTrait {
+    fn op(self) -> OpTrait {
+        match self {
+            Self::Sum => OpTrait::Add,
+            Self::Product => OpTrait::Mul,
+        }
+    }
+    fn to_path(self) -> Path {
+        match self {
+            Self::Sum => parse_quote!(::core::iter::Sum),
+            Self::Product => parse_quote!(::core::iter::Product),
+        }
+    }
+    fn to_func_ident(self) -> Ident {
+        match self {
+            Self::Sum => format_ident!("sum"),
+            Self::Product => format_ident!("product"),
+        }
+    }
+}
+
+impl Generator<'_> {
+    fn generate_iter_impl(&self, trait_: IterTrait, identity: &Identity, by_ref: bool) -> ItemImpl {
+        let rr_self_type = remove_reference(self.self_type);
+        let mut generics = self.implement.generics.clone();
+        let lifetime = fresh_lifetime(&generics, self.implement);
+        let (item_type, trait_args) = if by_ref {
+            generics.params.insert(0, parse_quote!(#lifetime));
+            (quote!(&#lifetime Self), quote!(<&#lifetime #rr_self_type>))
+        } else {
+            (quote!(Self), TokenStream::new())
+        };
+        self.update_where_clause(&mut generics, Operate(true, false, by_ref));
+        identity.update_where_clause(&mut generics, rr_self_type);
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+        let path = trait_.to_path();
+        let fn_name = trait_.to_func_ident();
+        let assign_fn_name = self.family.get_method(true);
+        let assign_trait = self.family.get_trait_path(
+            &self.implement.trait_.as_ref().unwrap().1,
+            true,
+            &parse_quote!(#item_type),
+        );
+        let init = identity.to_expr();
+        let (acc, x) = (local("acc"), local("x"));
+        let mut work: ItemImpl = parse_quote! {
+            impl #impl_generics #path #trait_args for #rr_self_type #where_clause {
+                fn #fn_name<I: ::core::iter::Iterator<Item = #item_type>>(iter: I) -> Self {
+                    iter.fold(#init, |mut #acc, #x| {
+                        <#rr_self_type as #assign_trait>::#assign_fn_name(&mut #acc, #x);
+                        #acc
+                    })
+                }
+            }
+        };
+        drop_unused_lifetimes(&mut work);
+        work
+    }
+    /// Generates `Sum` or `Product` for both `Item = T` and `Item = &T`.
+    pub(crate) fn generate_iter(
+        &self,
+        key: &Ident,
+        trait_: IterTrait,
+        identity: &Identity,
+    ) -> Result<TokenStream> {
+        if self.family.op != Some(trait_.op()) {
+            return Err(Error::new(
+                key.span(),
+                format!(
+                    "`{}` requires `{}` or `{}`",
+                    key,
+                    trait_.op(),
+                    trait_.op().to_assign()
+                ),
+            ));
+        }
+        if remove_reference(self.self_type) != remove_reference(self.rhs_type) {
+            return Err(Error::new(
+                key.span(),
+                format!(
+                    "`{}` requires that the types of LHS and RHS are the same",
+                    key
+                ),
+            ));
+        }
+        if !self.assign_available() {
+            return Err(self.output_error(key));
+        }
+        let owned = self.generate_iter_impl(trait_, identity, false);
+        let borrowed = self.generate_iter_impl(trait_, identity, true);
+        Ok(quote!(#owned #borrowed))
+    }
+}
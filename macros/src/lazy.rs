@@ -0,0 +1,244 @@
+use super::*;
+
+/// Returns the names of the expression node and the evaluation trait, e.g. `AddExpr` and `AddLazy`.
+fn get_names(op: OpTrait) -> (Ident, Ident) {
+    let op = op.to_non_assign().to_string();
+    (format_ident!("{}Expr", op), format_ident!("{}Lazy", op))
+}
+
+/// Returns the paths of the expression node and the evaluation trait, e.g. `module::AddExpr`.
+fn get_paths(op: OpTrait, module: Option<&Ident>) -> (TokenStream, TokenStream) {
+    let (expr, lazy) = get_names(op);
+    match module {
+        Some(module) => (quote!(#module::#expr), quote!(#module::#lazy)),
+        None => (quote!(#expr), quote!(#lazy)),
+    }
+}
+
+/// Makes `AddExpr` and `AddLazy`, which are shared by all types in a module.
+fn gen_common(op: OpTrait) -> TokenStream {
+    let (expr, lazy) = get_names(op);
+    let trait_ = op.to_non_assign();
+    let fn_name = trait_.to_func_ident();
+    let expr_doc = format!(" Unevaluated `{}` of `L` and `R`.", op.to_non_assign());
+    let lazy_doc = format!(" Evaluation of `{}` into `T`.", expr);
+    let apply_doc = format!(" Applies `{}` with `self` to `target`.", op.to_assign());
+    quote! {
+        #[doc = #expr_doc]
+        pub struct #expr<L, R>(pub L, pub R);
+        #[doc = #lazy_doc]
+        pub trait #lazy<T> {
+            /// Evaluates `self` into `T`.
+            fn eval(self) -> T;
+            #[doc = #apply_doc]
+            fn apply(self, target: &mut T);
+        }
+        impl<T, L: #lazy<T>, R: #lazy<T>> #lazy<T> for #expr<L, R> {
+            fn eval(self) -> T {
+                let mut acc = #lazy::eval(self.0);
+                #lazy::apply(self.1, &mut acc);
+                acc
+            }
+            fn apply(self, target: &mut T) {
+                #lazy::apply(self.0, target);
+                #lazy::apply(self.1, target);
+            }
+        }
+        impl<L, R, X> #trait_<X> for #expr<L, R> {
+            type Output = #expr<Self, X>;
+            fn #fn_name(self, rhs: X) -> Self::Output {
+                #expr(self, rhs)
+            }
+        }
+    }
+}
+
+impl Generator<'_> {
+    /// Makes `&T @ &T` returning `AddExpr<&T, &T>` instead of evaluating it.
+    pub(crate) fn generate_lazy(
+        &self,
+        op: Operate,
+        op_trait: OpTrait,
+        module: Option<&Ident>,
+    ) -> TokenStream {
+        let (expr, _) = get_paths(op_trait, module);
+        let rr_self_type = remove_reference(self.self_type);
+        let mut generics = self.implement.generics.clone();
+        let mut inserted = 0;
+        let mut get_type = |is_ref_: bool| {
+            if is_ref_ {
+                let lifetime = fresh_lifetime(&generics, self.implement);
+                generics.params.insert(inserted, parse_quote!(#lifetime));
+                inserted += 1;
+                quote!(&#lifetime #rr_self_type)
+            } else {
+                quote!(#rr_self_type)
+            }
+        };
+        let lhs_type = get_type(op.1);
+        let rhs_type = get_type(op.2);
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+        let trait_ = op_trait.to_non_assign();
+        let fn_name = trait_.to_func_ident();
+        let mut work: ItemImpl = parse_quote! {
+            impl #impl_generics #trait_<#rhs_type> for #lhs_type #where_clause {
+                type Output = #expr<#lhs_type, #rhs_type>;
+                fn #fn_name(self, rhs: #rhs_type) -> Self::Output {
+                    #expr(self, rhs)
+                }
+            }
+        };
+        drop_unused_lifetimes(&mut work);
+        quote!(#work)
+    }
+    /// Makes `AddLazy` for `T` and `&T`, `T + AddExpr`, `&T + AddExpr`, `T += AddExpr`
+    /// and `From<AddExpr> for T`.
+    fn generate_lazy_eval(&self, op_trait: OpTrait, module: Option<&Ident>) -> TokenStream {
+        let (expr, lazy) = get_paths(op_trait, module);
+        let self_type = self.self_type;
+        let assign_fn_name = op_trait.to_assign().to_func_ident();
+        let (impl_generics, _, where_clause) = self.implement.generics.split_for_impl();
+        let mut clone_generics = self.implement.generics.clone();
+        clone_generics
+            .make_where_clause()
+            .predicates
+            .push(parse_quote!(#self_type: ::core::clone::Clone));
+        let (_, _, clone_where_clause) = clone_generics.split_for_impl();
+        let mut expr_generics = self.implement.generics.clone();
+        let l = fresh_type_param(&expr_generics, "L");
+        expr_generics.params.push(parse_quote!(#l));
+        let r = fresh_type_param(&expr_generics, "R");
+        expr_generics.params.push(parse_quote!(#r));
+        expr_generics
+            .make_where_clause()
+            .predicates
+            .push(parse_quote!(#expr<#l, #r>: #lazy<#self_type>));
+        let (expr_impl_generics, _, expr_where_clause) = expr_generics.split_for_impl();
+        let mut node_generics = self.implement.generics.clone();
+        node_generics.params.push(parse_quote!(#l));
+        node_generics.params.push(parse_quote!(#r));
+        let (node_impl_generics, _, node_where_clause) = node_generics.split_for_impl();
+        let lifetime = fresh_lifetime(&node_generics, self.implement);
+        let mut ref_node_generics = node_generics.clone();
+        ref_node_generics.params.insert(0, parse_quote!(#lifetime));
+        let (ref_node_impl_generics, _, _) = ref_node_generics.split_for_impl();
+        let trait_ = op_trait.to_non_assign();
+        let fn_name = trait_.to_func_ident();
+        let assign_trait = op_trait.to_assign();
+        let impls: [ItemImpl; 6] = [
+            parse_quote! {
+                impl #node_impl_generics #trait_<#expr<#l, #r>> for #self_type #node_where_clause {
+                    type Output = #expr<Self, #expr<#l, #r>>;
+                    fn #fn_name(self, rhs: #expr<#l, #r>) -> Self::Output {
+                        #expr(self, rhs)
+                    }
+                }
+            },
+            parse_quote! {
+                impl #ref_node_impl_generics #trait_<#expr<#l, #r>> for &#lifetime #self_type #node_where_clause {
+                    type Output = #expr<Self, #expr<#l, #r>>;
+                    fn #fn_name(self, rhs: #expr<#l, #r>) -> Self::Output {
+                        #expr(self, rhs)
+                    }
+                }
+            },
+            parse_quote! {
+                impl #impl_generics #lazy<#self_type> for #self_type #where_clause {
+                    fn eval(self) -> #self_type {
+                        self
+                    }
+                    fn apply(self, target: &mut #self_type) {
+                        <#self_type as #assign_trait<&#self_type>>::#assign_fn_name(target, &self);
+                    }
+                }
+            },
+            parse_quote! {
+                impl #impl_generics #lazy<#self_type> for &#self_type #clone_where_clause {
+                    fn eval(self) -> #self_type {
+                        ::core::clone::Clone::clone(self)
+                    }
+                    fn apply(self, target: &mut #self_type) {
+                        <#self_type as #assign_trait<&#self_type>>::#assign_fn_name(target, self);
+                    }
+                }
+            },
+            parse_quote! {
+                impl #expr_impl_generics #assign_trait<#expr<#l, #r>> for #self_type #expr_where_clause {
+                    fn #assign_fn_name(&mut self, rhs: #expr<#l, #r>) {
+                        #lazy::apply(rhs, self);
+                    }
+                }
+            },
+            parse_quote! {
+                impl #expr_impl_generics ::core::convert::From<#expr<#l, #r>> for #self_type #expr_where_clause {
+                    fn from(expr: #expr<#l, #r>) -> Self {
+                        #lazy::eval(expr)
+                    }
+                }
+            },
+        ];
+        let mut result = TokenStream::new();
+        for mut work in impls {
+            drop_unused_lifetimes(&mut work);
+            result.extend(quote!(#work));
+        }
+        result
+    }
+}
+
+/// Checks that `#[auto_ops(lazy)]` is applicable and returns the operation.
+pub(crate) fn check_lazy(key: &Ident, generator: &Generator) -> Result<OpTrait> {
+    let op = generator.family.op.ok_or_else(|| {
+        Error::new(
+            key.span(),
+            format!("`{}` is not supported for `custom` operations", key),
+        )
+    })?;
+    if !op.is_associative() {
+        return Err(Error::new(
+            key.span(),
+            format!(
+                "`{}` requires an associative operation, but `{}` is not",
+                key, op
+            ),
+        ));
+    }
+    let rr_rhs_type = remove_reference(generator.rhs_type);
+    if generator.source_op != Operate(true, false, true) || rr_rhs_type != generator.self_type {
+        return Err(Error::new(
+            key.span(),
+            format!(
+                "`{}` requires the source `{}<&T> for T`",
+                key,
+                op.to_assign()
+            ),
+        ));
+    }
+    Ok(op)
+}
+
+/// Generates the expression node types and the evaluation of them.
+pub(crate) fn generate_lazy_common(
+    module: Option<&Ident>,
+    generator: &Generator,
+    op: OpTrait,
+) -> TokenStream {
+    let common = gen_common(op);
+    let common = if let Some(module) = module {
+        let doc = format!(
+            " Expression nodes of `{}` made by `#[auto_ops(lazy)]`.",
+            op.to_non_assign()
+        );
+        quote! {
+            #[doc = #doc]
+            pub mod #module {
+                use super::*;
+                #common
+            }
+        }
+    } else {
+        common
+    };
+    let eval = generator.generate_lazy_eval(op, module);
+    quote!(#common #eval)
+}
@@ -0,0 +1,1440 @@
+//! Proc-macros of `auto-impl-ops`, which should be used through the `auto-impl-ops` crate.
+mod args;
+mod borrow_rhs;
+mod broadcast;
+#[cfg(feature = "num-traits")]
+mod checked;
+mod cmp;
+mod derive;
+mod derive_ops;
+mod iter;
+mod lazy;
+mod lift;
+#[cfg(feature = "num-traits")]
+mod mul_add;
+mod take;
+#[cfg(test)]
+mod tests;
+#[cfg(test)]
+mod tests_instrument;
+mod unary;
+use args::Args;
+use iter::IterTrait;
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use std::collections::HashMap;
+use std::str::FromStr;
+use strum::{Display, EnumString};
+use syn::{
+    ext::IdentExt,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    visit_mut::{self, VisitMut},
+    *,
+};
+use take::TakeStrategy;
+use unary::UnaryOpTrait;
+
+fn is_ref(type_: &Type) -> bool {
+    matches!(type_, Type::Reference(_))
+}
+
+fn remove_reference(type_: &Type) -> &Type {
+    match type_ {
+        Type::Reference(ref_) => &ref_.elem,
+        _ => type_,
+    }
+}
+
+fn copy_reference(target: &Type, source: &Type) -> Type {
+    match source {
+        Type::Reference(inner) => {
+            let mut out = inner.clone();
+            *out.elem = target.clone();
+            Type::Reference(out)
+        }
+        _ => target.clone(),
+    }
+}
+
+/// Makes an identifier of a local variable in generated code, which never refers to user's items.
+fn local(name: &str) -> Ident {
+    Ident::new(name, Span::mixed_site())
+}
+
+/// Replaces lifetime parameters of an impl with `'_`.
+struct ElideLifetimes<'a>(&'a Generics);
+impl VisitMut for ElideLifetimes<'_> {
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        if self.0.lifetimes().any(|x| x.lifetime == *lifetime) {
+            *lifetime = Lifetime::new("'_", lifetime.span());
+        }
+    }
+}
+
+/// Returns `<T as Trait<U>>::method` of the trait implemented by `implement`.
+/// Lifetime parameters are elided to be inferred at the call site.
+fn qualified_method(implement: &ItemImpl, method: &Ident) -> ExprPath {
+    let self_type = &implement.self_ty;
+    let trait_ = &implement.trait_.as_ref().unwrap().1;
+    let mut path = parse_quote!(<#self_type as #trait_>::#method);
+    ElideLifetimes(&implement.generics).visit_expr_path_mut(&mut path);
+    path
+}
+
+fn get_last_segment(implement: &ItemImpl) -> Result<&PathSegment> {
+    if implement.trait_.is_none() {
+        return Err(Error::new(implement.span(), "Is not Trait impl"));
+    };
+    let trait_ = implement.trait_.as_ref().unwrap();
+    if let Some(bang) = trait_.0 {
+        return Err(Error::new(bang.span(), "Unexpected negative impl"));
+    }
+    let segments = &trait_.1.segments;
+    if segments.is_empty() {
+        return Err(Error::new(segments.span(), "Unexpected empty trait path"));
+    }
+    Ok(segments.last().unwrap())
+}
+
+fn get_rhs_type<'a>(args: &'a PathArguments, self_type: &'a Type) -> Result<&'a Type> {
+    match args {
+        PathArguments::None => Ok(self_type),
+        PathArguments::AngleBracketed(args) => {
+            let args = &args.args;
+            if args.len() != 1 {
+                return Err(Error::new(
+                    args.span(),
+                    "Number of trait arguments is not 1",
+                ));
+            }
+            if let GenericArgument::Type(rhs_type) = args.first().unwrap() {
+                Ok(rhs_type)
+            } else {
+                Err(Error::new(args.span(), "Is not type"))
+            }
+        }
+        _ => Err(Error::new(args.span(), "Unexpected trait arguments")),
+    }
+}
+
+fn get_output_type(implement: &ItemImpl, output: &Ident) -> Result<Type> {
+    let v = implement
+        .items
+        .iter()
+        .filter_map(|x| {
+            if let ImplItem::Type(x) = x {
+                Some(x)
+            } else {
+                None
+            }
+        })
+        .filter_map(|x| {
+            if x.ident == *output {
+                Some(&x.ty)
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    if let [x] = v[..] {
+        Ok(x.clone())
+    } else {
+        Err(Error::new(
+            Span::call_site(),
+            format!("`type {} =` is not found or multiple", output),
+        ))
+    }
+}
+
+/// Trait paths and method names of an operation such as `Add` and `AddAssign`.
+#[derive(Clone, Debug)]
+struct OpFamily {
+    /// `None` for custom operations
+    op: Option<OpTrait>,
+    trait_: Path,
+    method: Ident,
+    assign: Option<(Path, Ident)>,
+    output: Ident,
+}
+impl OpFamily {
+    fn new(op: OpTrait) -> Self {
+        let non_assign = op.to_non_assign();
+        let assign = op.to_assign();
+        Self {
+            op: Some(non_assign),
+            trait_: parse_quote!(#non_assign),
+            method: non_assign.to_func_ident(),
+            assign: Some((parse_quote!(#assign), assign.to_func_ident())),
+            output: format_ident!("Output"),
+        }
+    }
+    fn get_trait(&self, is_assign: bool) -> &Path {
+        if is_assign {
+            &self.assign.as_ref().unwrap().0
+        } else {
+            &self.trait_
+        }
+    }
+    fn get_method(&self, is_assign: bool) -> &Ident {
+        if is_assign {
+            &self.assign.as_ref().unwrap().1
+        } else {
+            &self.method
+        }
+    }
+    fn is_commutative(&self) -> bool {
+        self.op.map_or(false, OpTrait::is_commutative)
+    }
+    /// Returns whether `ident` is the assign trait of this family.
+    fn match_trait(&self, ident: &Ident) -> Result<bool> {
+        let last_ident = |x: &Path| x.segments.last().unwrap().ident == *ident;
+        if last_ident(&self.trait_) {
+            Ok(false)
+        } else if self.assign.as_ref().map_or(false, |x| last_ident(&x.0)) {
+            Ok(true)
+        } else {
+            Err(Error::new(
+                ident.span(),
+                format!("unexpacted Ident: {}", ident),
+            ))
+        }
+    }
+    /// Returns `Trait<rhs_type>` keeping the prefix of `base` if the trait is declared without it.
+    fn get_trait_path(&self, base: &Path, is_assign: bool, rhs_type: &Type) -> Path {
+        let path = self.get_trait(is_assign);
+        let mut trait_path = if path.leading_colon.is_some() || path.segments.len() > 1 {
+            path.clone()
+        } else {
+            base.clone()
+        };
+        let ident = &path.segments.last().unwrap().ident;
+        *trait_path.segments.last_mut().unwrap() = parse_quote! { #ident<#rhs_type> };
+        trait_path
+    }
+    /// Replaces trait of `implement` with `Trait<rhs_type>`.
+    fn set_trait(&self, implement: &mut ItemImpl, is_assign: bool, rhs_type: &Type) {
+        let trait_path = &mut implement.trait_.as_mut().unwrap().1;
+        *trait_path = self.get_trait_path(trait_path, is_assign, rhs_type);
+    }
+}
+
+/// Returns whether `attrs` contain `#[track_caller]`.
+fn is_track_caller(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| attr.path.is_ident("track_caller"))
+}
+
+/// Returns whether a method of `implement` has `#[track_caller]`.
+fn has_track_caller(implement: &ItemImpl) -> bool {
+    implement.items.iter().any(|item| match item {
+        ImplItem::Method(method) => is_track_caller(&method.attrs),
+        _ => false,
+    })
+}
+
+/// Adds `#[track_caller]` to the methods of `implement` which do not have it.
+fn add_track_caller(implement: &mut ItemImpl) {
+    for item in implement.items.iter_mut() {
+        match item {
+            ImplItem::Method(method) if !is_track_caller(&method.attrs) => {
+                method.attrs.push(parse_quote!(#[track_caller]));
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Makes statements counting calls, clones and `Default` swaps of the variant if `instrument`.
+fn gen_instrument(
+    instrument: bool,
+    private: &Path,
+    self_type: &Type,
+    variant: &str,
+    clones: u64,
+    defaults: u64,
+) -> TokenStream {
+    if instrument {
+        let rr_self_type = remove_reference(self_type);
+        quote! {
+            static COUNTER: #private::Counter = #private::Counter::new(#variant);
+            COUNTER.record(::core::any::type_name::<#rr_self_type>(), #clones, #defaults);
+        }
+    } else {
+        TokenStream::new()
+    }
+}
+
+/// Returns an error for `key` which requires assign variants while `Output` of the source is not `T`.
+fn output_error(key: &Ident, self_type: &Type, output: &impl ToTokens) -> Error {
+    let output = output.to_token_stream();
+    let self_type = remove_reference(self_type).to_token_stream();
+    Error::new(
+        key.span(),
+        format!(
+            "`{}` requires that `Output` is `{}`, but it is `{}` (use `output_into` to convert it by `Into<{}>`)",
+            key, self_type, output, self_type
+        ),
+    )
+}
+
+/// `Operate(is_assign, is_lhs_ref, is_rhs_ref)`
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+struct Operate(bool, bool, bool);
+impl Operate {
+    fn lhs_move(&self) -> bool {
+        !self.0 && !self.1
+    }
+    fn rhs_move(&self) -> bool {
+        !self.2
+    }
+    fn require_lhs_clone(&self, op: Self) -> bool {
+        (self.lhs_move() || self.0) && op.1
+    }
+    fn require_rhs_clone(&self, op: Self) -> bool {
+        self.rhs_move() && op.2
+    }
+    fn name(&self) -> String {
+        let ref_val = |x: bool| if x { "ref" } else { "val" };
+        let lhs = if self.0 { "assign" } else { ref_val(self.1) };
+        format!("{}_{}", lhs, ref_val(self.2))
+    }
+}
+
+#[derive(Clone, Debug)]
+/// The source as seen from assign variants, shared by `Generator` and `MulAddGenerator`.
+struct AssignSource<'a> {
+    /// `<T as AddAssign<&U>>::add_assign` of the source
+    method: ExprPath,
+    /// operands of the source other than LHS, such as `rhs` or `a, b`
+    operands: TokenStream,
+    source_op: Operate,
+    /// `T` without reference
+    self_type: &'a Type,
+    take: &'a TakeStrategy,
+    private: &'a Path,
+    other_output: &'a Option<Type>,
+    output_into: bool,
+}
+impl AssignSource<'_> {
+    /// Returns whether assign variants can be made,
+    /// i.e. `Output` of the source is `T` or `output_into` is given.
+    fn available(&self) -> bool {
+        self.other_output.is_none() || self.output_into
+    }
+    /// Adds `Clone` bounds of `clone_types` and bounds required by `take` and `output_into`
+    /// if the variant is an assign variant.
+    fn update_where_clause(
+        &self,
+        generics: &mut Generics,
+        clone_types: &[(bool, &Type)],
+        is_assign: bool,
+    ) {
+        let self_type = self.self_type;
+        let take = self.source_op.lhs_move() && is_assign;
+        let take_clone = (take && matches!(self.take, TakeStrategy::Clone), self_type);
+        let mut bounded = Vec::new();
+        for (_, type_) in clone_types.iter().chain([&take_clone]).filter(|x| x.0) {
+            if !bounded.contains(type_) {
+                bounded.push(*type_);
+                let wc = generics.make_where_clause();
+                wc.predicates.push(parse_quote! {
+                    #type_: ::core::clone::Clone
+                });
+            }
+        }
+        if take && matches!(self.take, TakeStrategy::Default) {
+            let wc = generics.make_where_clause();
+            wc.predicates.push(parse_quote! {
+                #self_type: ::core::default::Default
+            });
+        }
+        if let (Some(output), true) = (self.other_output, is_assign) {
+            let wc = generics.make_where_clause();
+            wc.predicates.push(parse_quote! {
+                #output: ::core::convert::Into<#self_type>
+            });
+        }
+    }
+    /// Returns `source_method(lhs, rhs)` converted into `T` if `Output` of the source is not `T`.
+    fn gen_value(&self, lhs: TokenStream) -> TokenStream {
+        let (method, operands) = (&self.method, &self.operands);
+        let value = quote!(#method(#lhs, #operands));
+        if self.other_output.is_some() {
+            quote!(::core::convert::Into::into(#value))
+        } else {
+            value
+        }
+    }
+    /// Makes the body of assign variants.
+    fn gen_body(&self) -> TokenStream {
+        if self.source_op.0 {
+            let (method, operands) = (&self.method, &self.operands);
+            quote! {
+                #method(self, #operands);
+            }
+        } else if self.source_op.1 {
+            let value = self.gen_value(quote!(&*self));
+            quote! {
+                *self = #value;
+            }
+        } else {
+            self.take.gen_body(self.private, |x| self.gen_value(x))
+        }
+    }
+}
+
+struct Generator<'a> {
+    implement: &'a ItemImpl,
+    family: &'a OpFamily,
+    source_op: Operate,
+    self_type: &'a Type,
+    rhs_type: &'a Type,
+    /// whether `commutative` is given
+    commutative: bool,
+    /// whether generated methods have `#[track_caller]`
+    track_caller: bool,
+    /// how assign variants move out of `&mut self`
+    take: &'a TakeStrategy,
+    /// `::auto_impl_ops::__private`, which generated code refers to
+    private: &'a Path,
+    /// `Output` of the source if it is not `T`
+    other_output: Option<Type>,
+    /// whether `output_into` is given
+    output_into: bool,
+    /// whether generated methods count calls
+    instrument: bool,
+}
+impl Generator<'_> {
+    /// Returns `T` or `&T` with an elided lifetime, which is independent of the other operand.
+    fn get_arg_type(is_ref_: bool, target: &Type) -> Type {
+        if !is_ref_ {
+            remove_reference(target).clone()
+        } else if is_ref(target) {
+            target.clone()
+        } else {
+            parse_quote! {
+                &#target
+            }
+        }
+    }
+    /// Returns whether `&T @ T` can be made as `rhs @= self` without cloning `self`.
+    fn reuse_rhs(&self, op: Operate) -> bool {
+        self.commutative
+            && self.family.is_commutative()
+            && op == Operate(false, true, false)
+            && !self.source_op.1
+            && self.source_op.2
+            && remove_reference(self.self_type) == remove_reference(self.rhs_type)
+    }
+    /// Returns why `op` requires `Clone` or `Default`, if it does.
+    fn fallback_reason(&self, op: Operate) -> Option<String> {
+        let rr_self_type = remove_reference(self.self_type);
+        let rr_rhs_type = remove_reference(self.rhs_type);
+        if self.source_op.require_lhs_clone(op) && !self.reuse_rhs(op) {
+            Some(format!(
+                "it clones LHS `{}`",
+                rr_self_type.to_token_stream()
+            ))
+        } else if self.source_op.require_rhs_clone(op) {
+            Some(format!("it clones RHS `{}`", rr_rhs_type.to_token_stream()))
+        } else if self.source_op.lhs_move() && op.0 {
+            self.take.fallback_reason(rr_self_type)
+        } else {
+            None
+        }
+    }
+    /// Returns an error if `strict` is given and the variant `name` requires `Clone` or `Default`
+    /// without `allow_clone(name)`.
+    fn check_strict(&self, args: &Args, name: &str, op: Operate) -> Result<()> {
+        let key = match &args.strict {
+            Some(key) => key,
+            None => return Ok(()),
+        };
+        if let Some((_, allowed)) = &args.allow_clone {
+            if allowed.iter().any(|x| x == name) {
+                return Ok(());
+            }
+        }
+        if let Some(reason) = self.fallback_reason(op) {
+            return Err(Error::new(
+                key.span(),
+                format!(
+                    "`{0}` is not generated in `{1}` mode because {2} (use `allow_clone({0})` to allow it)",
+                    name, key, reason
+                ),
+            ));
+        }
+        Ok(())
+    }
+    fn update_where_clause(&self, generics: &mut Generics, op: Operate) {
+        let clone_types = [
+            (
+                self.source_op.require_lhs_clone(op) && !self.reuse_rhs(op),
+                remove_reference(self.self_type),
+            ),
+            (
+                self.source_op.require_rhs_clone(op),
+                remove_reference(self.rhs_type),
+            ),
+        ];
+        self.assign_source()
+            .update_where_clause(generics, &clone_types, op.0);
+    }
+    /// Returns `<T as AddAssign<&U>>::add_assign` of the source.
+    fn source_method(&self) -> ExprPath {
+        qualified_method(self.implement, self.family.get_method(self.source_op.0))
+    }
+    fn assign_source(&self) -> AssignSource<'_> {
+        AssignSource {
+            method: self.source_method(),
+            operands: local("rhs").into_token_stream(),
+            source_op: self.source_op,
+            self_type: remove_reference(self.self_type),
+            take: self.take,
+            private: self.private,
+            other_output: &self.other_output,
+            output_into: self.output_into,
+        }
+    }
+    fn assign_available(&self) -> bool {
+        self.assign_source().available()
+    }
+    /// Returns an error for `key` which requires assign variants unavailable by `Output` of the source.
+    fn output_error(&self, key: &Ident) -> Error {
+        output_error(key, self.self_type, &self.other_output)
+    }
+    fn gen_rhs(source_op: Operate, op: Operate) -> TokenStream {
+        let rhs = local("rhs");
+        #[allow(clippy::collapsible_else_if)]
+        if source_op.2 {
+            if op.2 {
+                TokenStream::new()
+            } else {
+                quote!(let #rhs = &#rhs;)
+            }
+        } else {
+            if op.2 {
+                quote!(let #rhs = ::core::clone::Clone::clone(#rhs);)
+            } else {
+                TokenStream::new()
+            }
+        }
+    }
+    fn gen_lhs(source_op: Operate, op: Operate) -> TokenStream {
+        let lhs = local("lhs");
+        #[allow(clippy::collapsible_else_if)]
+        if source_op.0 {
+            if op.1 {
+                quote!(let mut #lhs = ::core::clone::Clone::clone(self);)
+            } else {
+                quote!(let mut #lhs = self;)
+            }
+        } else if source_op.1 {
+            if op.1 {
+                quote!(let #lhs = self;)
+            } else {
+                quote!(let #lhs = &self;)
+            }
+        } else {
+            if op.1 {
+                quote!(let #lhs = ::core::clone::Clone::clone(self);)
+            } else {
+                quote!(let #lhs = self;)
+            }
+        }
+    }
+    fn gen_output(&self) -> Result<Type> {
+        if self.source_op.0 {
+            Ok(remove_reference(self.self_type).clone())
+        } else {
+            get_output_type(self.implement, &self.family.output)
+        }
+    }
+    fn gen_track_caller(&self) -> TokenStream {
+        if self.track_caller {
+            quote!(#[track_caller])
+        } else {
+            TokenStream::new()
+        }
+    }
+    fn generate(&self, op: Operate) -> Result<TokenStream> {
+        if op.0 && op.1 {
+            return Err(Error::new(
+                Span::call_site(),
+                "Type of LHS of assign operations must not reference",
+            ));
+        }
+        if op == self.source_op {
+            return Ok(self.implement.to_token_stream());
+        }
+        let mut work = self.implement.clone();
+        let rhs_type = Self::get_arg_type(op.2, self.rhs_type);
+        self.family.set_trait(&mut work, op.0, &rhs_type);
+        *work.self_ty.as_mut() = Self::get_arg_type(op.1, self.self_type);
+        self.update_where_clause(&mut work.generics, op);
+        work.items.clear();
+        let fn_name = self.family.get_method(op.0);
+        let take = self.source_op.lhs_move() && op.0;
+        let (take_clones, defaults) = if take { self.take.counts() } else { (0, 0) };
+        let clones = (self.source_op.require_lhs_clone(op) && !self.reuse_rhs(op)) as u64
+            + self.source_op.require_rhs_clone(op) as u64
+            + take_clones;
+        let instrument = gen_instrument(
+            self.instrument,
+            self.private,
+            self.self_type,
+            &op.name(),
+            clones,
+            defaults,
+        );
+        let track_caller = self.gen_track_caller();
+        let lhs = local("lhs");
+        let rhs = local("rhs");
+        let preamble_rhs = Self::gen_rhs(self.source_op, op);
+        if op.0 {
+            if take {
+                work.attrs.extend(self.take.gen_doc());
+            }
+            let body = self.assign_source().gen_body();
+            work.items.push(parse_quote! {
+                #track_caller
+                fn #fn_name(&mut self, #rhs: #rhs_type) {
+                    #instrument
+                    #preamble_rhs
+                    #body
+                }
+            });
+        } else {
+            let output = &self.family.output;
+            let output_type = self.gen_output()?;
+            work.items.push(parse_quote! {
+                type #output = #output_type;
+            });
+            let source_method = self.source_method();
+            let (preamble, body) = if self.reuse_rhs(op) {
+                let body = if self.source_op.0 {
+                    quote! {
+                        let mut #lhs = #rhs;
+                        #source_method(&mut #lhs, self);
+                        #lhs
+                    }
+                } else {
+                    quote! {
+                        #source_method(#rhs, self)
+                    }
+                };
+                (TokenStream::new(), body)
+            } else {
+                let preamble_lhs = Self::gen_lhs(self.source_op, op);
+                let body = if self.source_op.0 {
+                    quote! {
+                        #source_method(&mut #lhs, #rhs);
+                        #lhs
+                    }
+                } else {
+                    quote! {
+                        #source_method(#lhs, #rhs)
+                    }
+                };
+                (quote!(#preamble_lhs #preamble_rhs), body)
+            };
+            work.items.push(parse_quote! {
+                #track_caller
+                fn #fn_name(self, #rhs: #rhs_type) -> Self::#output {
+                    #instrument
+                    #preamble
+                    #body
+                }
+            });
+        }
+        drop_unused_lifetimes(&mut work);
+        Ok(quote!(#work))
+    }
+    fn generate_mirror(&self, lhs_ref: bool, rhs_ref: bool) -> Result<TokenStream> {
+        let forward_op = Operate(false, rhs_ref, lhs_ref);
+        let mut work = self.implement.clone();
+        let lhs_type = Self::get_arg_type(lhs_ref, self.rhs_type);
+        let rhs_type = Self::get_arg_type(rhs_ref, self.self_type);
+        let forward_trait =
+            self.family
+                .get_trait_path(&work.trait_.as_ref().unwrap().1, false, &lhs_type);
+        self.family.set_trait(&mut work, false, &rhs_type);
+        *work.self_ty.as_mut() = lhs_type;
+        self.update_where_clause(&mut work.generics, forward_op);
+        work.items.clear();
+        let output = &self.family.output;
+        let output_type = self.gen_output()?;
+        work.items.push(parse_quote! {
+            type #output = #output_type;
+        });
+        let fn_name = self.family.get_method(false);
+        let ref_val = |x: bool| if x { "ref" } else { "val" };
+        let variant = format!("rev_{}_{}", ref_val(lhs_ref), ref_val(rhs_ref));
+        let instrument =
+            gen_instrument(self.instrument, self.private, self.rhs_type, &variant, 0, 0);
+        let track_caller = self.gen_track_caller();
+        let rhs = local("rhs");
+        work.items.push(parse_quote! {
+            #track_caller
+            fn #fn_name(self, #rhs: #rhs_type) -> Self::#output {
+                #instrument
+                <#rhs_type as #forward_trait>::#fn_name(#rhs, self)
+            }
+        });
+        drop_unused_lifetimes(&mut work);
+        Ok(quote!(#work))
+    }
+    fn generate_mirrors(&self) -> Result<TokenStream> {
+        let mut result = TokenStream::new();
+        if remove_reference(self.self_type) == remove_reference(self.rhs_type) {
+            return Ok(result);
+        }
+        for (lhs_ref, rhs_ref) in [(true, true), (true, false), (false, true), (false, false)] {
+            result.extend(self.generate_mirror(lhs_ref, rhs_ref)?);
+        }
+        Ok(result)
+    }
+}
+
+/// Collects lifetimes used in an impl except the declarations of lifetime parameters.
+struct CollectLifetimes(Vec<Lifetime>);
+impl VisitMut for CollectLifetimes {
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        if !self.0.contains(lifetime) {
+            self.0.push(lifetime.clone());
+        }
+    }
+    fn visit_lifetime_def_mut(&mut self, def: &mut LifetimeDef) {
+        for bound in def.bounds.iter_mut() {
+            self.visit_lifetime_mut(bound);
+        }
+    }
+}
+
+/// Removes lifetime parameters which are not used in `implement`.
+fn drop_unused_lifetimes(implement: &mut ItemImpl) {
+    let mut used = CollectLifetimes(Vec::new());
+    used.visit_item_impl_mut(implement);
+    let params = std::mem::take(&mut implement.generics.params);
+    implement.generics.params = params
+        .into_iter()
+        .filter(|x| match x {
+            GenericParam::Lifetime(x) => used.0.contains(&x.lifetime),
+            _ => true,
+        })
+        .collect();
+}
+
+/// Collects the names of lifetimes in `tokens`, including ones bound by `for<'x>`.
+fn collect_lifetimes(tokens: TokenStream, lifetimes: &mut Vec<Ident>) {
+    let mut after_quote = false;
+    for token in tokens {
+        let quote = matches!(&token, proc_macro2::TokenTree::Punct(x) if x.as_char() == '\'');
+        match token {
+            proc_macro2::TokenTree::Ident(ident) if after_quote => lifetimes.push(ident),
+            proc_macro2::TokenTree::Group(group) => collect_lifetimes(group.stream(), lifetimes),
+            _ => (),
+        }
+        after_quote = quote;
+    }
+}
+
+/// Returns a lifetime which is neither declared in `generics` nor used anywhere in `implement`.
+fn fresh_lifetime(generics: &Generics, implement: &ItemImpl) -> Lifetime {
+    let mut used = Vec::new();
+    collect_lifetimes(implement.to_token_stream(), &mut used);
+    ('a'..='z')
+        .map(|c| c.to_string())
+        .chain((0..).map(|i| format!("a{}", i)))
+        .map(|x| Lifetime::new(&format!("'{}", x), Span::call_site()))
+        .find(|x| generics.lifetimes().all(|y| &y.lifetime != x) && !used.contains(&x.ident))
+        .unwrap()
+}
+
+/// Returns `base`, `base0`, `base1`, ... which is not a type parameter of `generics`.
+fn fresh_type_param(generics: &Generics, base: &str) -> Ident {
+    std::iter::once(base.to_string())
+        .chain((0..).map(|i| format!("{}{}", base, i)))
+        .map(|x| Ident::new(&x, Span::call_site()))
+        .find(|x| generics.type_params().all(|y| &y.ident != x))
+        .unwrap()
+}
+
+/// Returns the type parameter `R` if `type_` is `R` or `&R`.
+fn get_type_param<'a>(type_: &'a Type, generics: &Generics) -> Option<&'a Ident> {
+    if let Type::Path(TypePath { qself: None, path }) = remove_reference(type_) {
+        let ident = path.get_ident()?;
+        generics
+            .type_params()
+            .any(|x| &x.ident == ident)
+            .then(|| ident)
+    } else {
+        None
+    }
+}
+
+struct ReplaceTypeParam<'a> {
+    param: &'a Ident,
+    type_: &'a Type,
+}
+impl VisitMut for ReplaceTypeParam<'_> {
+    fn visit_type_mut(&mut self, type_: &mut Type) {
+        if let Type::Path(TypePath { qself: None, path }) = type_ {
+            if path.is_ident(self.param) {
+                *type_ = self.type_.clone();
+                return;
+            }
+            if path.leading_colon.is_none() && path.segments[0].ident == *self.param {
+                let replaced = self.type_;
+                let rest = path.segments.iter().skip(1);
+                *type_ = parse_quote!(<#replaced>#(::#rest)*);
+            }
+        }
+        visit_mut::visit_type_mut(self, type_);
+    }
+}
+
+/// Applies `visitor` to the trait arguments, generics and associated types of `implement`.
+/// Methods are kept as is because they are emitted only in the source impl.
+fn visit_impl_header_mut(visitor: &mut impl VisitMut, implement: &mut ItemImpl) {
+    visitor.visit_generics_mut(&mut implement.generics);
+    if let Some((_, path, _)) = &mut implement.trait_ {
+        visitor.visit_path_mut(path);
+    }
+    for item in implement.items.iter_mut() {
+        if let ImplItem::Type(item) = item {
+            visitor.visit_type_mut(&mut item.ty);
+        }
+    }
+}
+
+/// Removes `Type::Group` made by `macro_rules!` and redundant `Type::Paren`.
+struct Ungroup;
+impl VisitMut for Ungroup {
+    fn visit_type_mut(&mut self, type_: &mut Type) {
+        loop {
+            let elem = match type_ {
+                Type::Group(TypeGroup { elem, .. }) | Type::Paren(TypeParen { elem, .. })
+                    if !matches!(elem.as_ref(), Type::TraitObject(_) | Type::ImplTrait(_)) =>
+                {
+                    elem.as_ref().clone()
+                }
+                _ => break,
+            };
+            *type_ = elem;
+        }
+        visit_mut::visit_type_mut(self, type_);
+    }
+}
+
+/// Removes `Type::Group` and `Type::Paren` wrapping types of `implement`,
+/// so that references and the equality of types can be detected.
+fn ungroup_types(implement: &mut ItemImpl) {
+    Ungroup.visit_type_mut(&mut implement.self_ty);
+    visit_impl_header_mut(&mut Ungroup, implement);
+}
+
+/// Replaces `Self` in the trait arguments, generics and associated types of `implement`
+/// with the self type, which differs in each variant.
+fn resolve_self(implement: &mut ItemImpl) {
+    let param = format_ident!("Self");
+    let self_type = implement.self_ty.as_ref().clone();
+    let mut visitor = ReplaceTypeParam {
+        param: &param,
+        type_: &self_type,
+    };
+    visit_impl_header_mut(&mut visitor, implement);
+}
+
+/// Replaces type parameter `param` of `implement` with the concrete type `type_`.
+fn replace_type_param(implement: &ItemImpl, param: &Ident, type_: &Type) -> ItemImpl {
+    let mut work = implement.clone();
+    work.generics.params = work
+        .generics
+        .params
+        .into_iter()
+        .filter(|x| !matches!(x, GenericParam::Type(x) if &x.ident == param))
+        .collect();
+    ReplaceTypeParam { param, type_ }.visit_item_impl_mut(&mut work);
+    work
+}
+
+type Attributes = Punctuated<Ident, token::Comma>;
+fn auto_ops_generate(args: Args, mut implement: ItemImpl) -> Result<TokenStream> {
+    #[cfg(feature = "num-traits")]
+    if implement.trait_.is_none() {
+        Args {
+            track_caller: None,
+            ..args.clone()
+        }
+        .reject_binary_options()?;
+        return checked::auto_ops_generate_inherent(&args, &implement);
+    }
+    let private = args.private_path();
+    if args.track_caller.is_some() {
+        add_track_caller(&mut implement);
+    }
+    ungroup_types(&mut implement);
+    resolve_self(&mut implement);
+    let last_segment = get_last_segment(&implement)?;
+    if let Ok(op) = UnaryOpTrait::from_str(&last_segment.ident.to_string()) {
+        let args = Args {
+            track_caller: None,
+            ..args
+        };
+        args.reject_binary_options()?;
+        return unary::auto_ops_generate_unary(
+            args.variants,
+            &implement,
+            op,
+            &private,
+            args.instrument,
+        );
+    }
+    if let Ok(trait_) = cmp::CmpTrait::from_str(&last_segment.ident.to_string()) {
+        args.reject_binary_options()?;
+        return cmp::auto_ops_generate_cmp(&args.variants, &implement, trait_);
+    }
+    #[cfg(feature = "num-traits")]
+    if last_segment.ident == "MulAdd" || last_segment.ident == "MulAddAssign" {
+        let take = TakeStrategy::from_arg(&args.take);
+        let output_into = args.output_into.is_some();
+        let args = Args {
+            take: None,
+            output_into: None,
+            track_caller: None,
+            ..args
+        };
+        args.reject_binary_options()?;
+        let is_assign = last_segment.ident == "MulAddAssign";
+        return mul_add::auto_ops_generate_mul_add(
+            &args.variants,
+            &implement,
+            is_assign,
+            &take,
+            output_into,
+            &private,
+            args.instrument,
+        );
+    }
+    #[cfg(feature = "num-traits")]
+    if let Ok(op) = checked::CheckedOpTrait::from_str(&last_segment.ident.to_string()) {
+        let args = Args {
+            track_caller: None,
+            ..args
+        };
+        args.reject_binary_options()?;
+        return checked::auto_ops_generate_checked(&args, &implement, op);
+    }
+    let family = match &args.custom {
+        Some((_, family)) => family.clone(),
+        None => OpFamily::new(last_segment.ident.clone().try_into()?),
+    };
+    let is_assign = family.match_trait(&last_segment.ident)?;
+    if let Some((key, _)) = &args.commutative {
+        if !family.is_commutative() && args.force_commutative.is_none() {
+            let name = &family.trait_.segments.last().unwrap().ident;
+            return Err(Error::new(
+                key.span(),
+                format!(
+                    "`{}` is not commutative (use `force_commutative` to generate mirrored impls anyway)",
+                    name
+                ),
+            ));
+        }
+    } else if let Some(key) = &args.force_commutative {
+        return Err(Error::new(
+            key.span(),
+            "`force_commutative` requires `commutative`",
+        ));
+    }
+    let self_type = &implement.self_ty;
+    let rhs_type = get_rhs_type(&last_segment.arguments, self_type)?;
+    let take = TakeStrategy::from_arg(&args.take);
+    let other_output = if is_assign {
+        None
+    } else {
+        Some(get_output_type(&implement, &family.output)?)
+            .filter(|output| output != remove_reference(self_type))
+    };
+    let generator = Generator {
+        implement: &implement,
+        family: &family,
+        source_op: Operate(is_assign, is_ref(self_type), is_ref(rhs_type)),
+        self_type,
+        rhs_type,
+        commutative: args.commutative.is_some(),
+        track_caller: has_track_caller(&implement),
+        take: &take,
+        private: &private,
+        other_output,
+        output_into: args.output_into.is_some(),
+        instrument: args.instrument,
+    };
+    let list = [
+        ("assign_ref", Operate(true, false, true)),
+        ("assign_val", Operate(true, false, false)),
+        ("ref_ref", Operate(false, true, true)),
+        ("ref_val", Operate(false, true, false)),
+        ("val_ref", Operate(false, false, true)),
+        ("val_val", Operate(false, false, false)),
+    ];
+    let map = HashMap::from(list);
+    let rev_map = list.iter().map(|&(v, k)| (k, v)).collect::<HashMap<_, _>>();
+    let mut attrs = args.variants.clone();
+    if attrs.is_empty() {
+        attrs = list
+            .iter()
+            .filter(|(_, op)| !op.0 || (family.assign.is_some() && generator.assign_available()))
+            .map(|(x, _)| format_ident!("{}", x))
+            .collect();
+    }
+    let source = rev_map[&generator.source_op];
+    if !attrs.iter().any(|x| x == source) {
+        attrs.push(format_ident!("{}", source));
+    }
+    // `Sum` and `Product` fold items with the assign variants,
+    // and operations derived from the source call `T @= &U`.
+    let mut required = Vec::new();
+    if args.sum.is_some() || args.product.is_some() {
+        required.extend(["assign_ref", "assign_val"]);
+    }
+    if let (Some((_, derive_ops)), Some(op)) = (&args.derive_ops, family.op) {
+        if derive_ops.iter().any(|x| x.is_based_on(op)) {
+            required.push("assign_ref");
+        }
+    }
+    if family.assign.is_some() && generator.assign_available() {
+        for name in required {
+            if !attrs.iter().any(|x| x == name) {
+                attrs.push(format_ident!("{}", name));
+            }
+        }
+    }
+    let lazy = match &args.lazy {
+        Some((key, module)) => Some((lazy::check_lazy(key, &generator)?, module.as_ref())),
+        None => None,
+    };
+    let mut result = TokenStream::new();
+    if let Some(key) = &args.borrow_rhs {
+        result.extend(borrow_rhs::generate_borrow_rhs(key, &args, &generator)?);
+    } else {
+        for i in attrs.iter() {
+            let s = i.to_string();
+            if let Some(op) = map.get(s.as_str()) {
+                if let (Some((op_trait, module)), false) = (lazy, op.0) {
+                    generator.check_strict(&args, &s, *op)?;
+                    result.extend(generator.generate_lazy(*op, op_trait, module));
+                    continue;
+                }
+                if op.0 && family.assign.is_none() {
+                    return Err(Error::new(
+                        i.span(),
+                        format!("`{}` requires `assign` and `assign_method` of `custom`", i),
+                    ));
+                }
+                if op.0 && !generator.assign_available() {
+                    return Err(generator.output_error(i));
+                }
+                if *op != generator.source_op {
+                    generator.check_strict(&args, &s, *op)?;
+                }
+                let code = generator.generate(*op)?;
+                result.extend(code);
+            }
+        }
+    }
+    if let Some((op_trait, module)) = lazy {
+        result.extend(lazy::generate_lazy_common(module, &generator, op_trait));
+    }
+    if let Some((key, types)) = &args.commutative {
+        if remove_reference(self_type) != remove_reference(rhs_type) || !types.is_empty() {
+            for (name, op) in list.iter().filter(|(_, op)| !op.0) {
+                if *op != generator.source_op {
+                    generator.check_strict(&args, name, *op)?;
+                }
+            }
+        }
+        if types.is_empty() {
+            if let Some(param) = get_type_param(rhs_type, &implement.generics) {
+                return Err(Error::new(
+                    key.span(),
+                    format!(
+                        "`{}` cannot implement mirrored impls for the type parameter `{}` because of the orphan rule (use `{}(T1, T2, ...)` to list the types of `{}`)",
+                        key, param, key, param
+                    ),
+                ));
+            }
+            result.extend(generator.generate_mirrors()?);
+        }
+        for type_ in types {
+            let param = get_type_param(rhs_type, &implement.generics).ok_or_else(|| {
+                Error::new(
+                    rhs_type.span(),
+                    "`commutative(...)` requires that the type of RHS is a type parameter",
+                )
+            })?;
+            let work = replace_type_param(&implement, param, type_);
+            let last_segment = get_last_segment(&work)?;
+            let generator = Generator {
+                implement: &work,
+                family: &family,
+                source_op: generator.source_op,
+                self_type: &work.self_ty,
+                rhs_type: get_rhs_type(&last_segment.arguments, &work.self_ty)?,
+                commutative: true,
+                track_caller: generator.track_caller,
+                take: generator.take,
+                private: generator.private,
+                other_output: generator.other_output.clone(),
+                output_into: generator.output_into,
+                instrument: generator.instrument,
+            };
+            result.extend(generator.generate_mirrors()?);
+        }
+    }
+    if let Some((_, scalar_types)) = &args.broadcast {
+        result.extend(broadcast::generate_broadcast(
+            &args,
+            &implement,
+            generator.source_op,
+            rhs_type,
+            scalar_types,
+        )?);
+    }
+    if let Some((key, identity)) = &args.sum {
+        result.extend(generator.generate_iter(key, IterTrait::Sum, identity)?);
+    }
+    if let Some((key, identity)) = &args.product {
+        result.extend(generator.generate_iter(key, IterTrait::Product, identity)?);
+    }
+    if let Some((key, derive_ops)) = &args.derive_ops {
+        let op = family.op.ok_or_else(|| {
+            Error::new(
+                key.span(),
+                format!("`{}` is not supported for `custom` operations", key),
+            )
+        })?;
+        result.extend(derive_ops::generate_derived(
+            key, &args, derive_ops, &generator, op,
+        )?);
+    }
+    if let Some((key, module)) = &args.lift {
+        result.extend(lift::generate_lift(key, module.as_ref(), &family)?);
+    }
+    Ok(result)
+}
+
+#[derive(Clone, Copy, Debug, Display, EnumString, PartialEq, Eq, Hash)]
+enum OpTrait {
+    Add,
+    AddAssign,
+    Sub,
+    SubAssign,
+    Mul,
+    MulAssign,
+    Div,
+    DivAssign,
+    Rem,
+    RemAssign,
+    BitAnd,
+    BitAndAssign,
+    BitOr,
+    BitOrAssign,
+    BitXor,
+    BitXorAssign,
+    Shl,
+    ShlAssign,
+    Shr,
+    ShrAssign,
+}
+impl TryFrom<Ident> for OpTrait {
+    type Error = Error;
+    fn try_from(ident: Ident) -> Result<Self> {
+        if let Ok(x) = Self::from_str(&ident.to_string()) {
+            Ok(x)
+        } else {
+            Err(Error::new(
+                ident.span(),
+                format!("unexpacted Ident: {}", ident),
+            ))
+        }
+    }
+}
+impl ToTokens for OpTrait {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ident = Ident::new(&self.to_string(), Span::call_site());
+        tokens.extend(quote!(::core::ops::#ident));
+    }
+}
+
+impl OpTrait {
+    fn to_assign(self) -> Self {
+        use OpTrait::*;
+        match self {
+            Add | AddAssign => AddAssign,
+            Sub | SubAssign => SubAssign,
+            Mul | MulAssign => MulAssign,
+            Div | DivAssign => DivAssign,
+            Rem | RemAssign => RemAssign,
+            BitAnd | BitAndAssign => BitAndAssign,
+            BitOr | BitOrAssign => BitOrAssign,
+            BitXor | BitXorAssign => BitXorAssign,
+            Shl | ShlAssign => ShlAssign,
+            Shr | ShrAssign => ShrAssign,
+        }
+    }
+    fn to_non_assign(self) -> Self {
+        use OpTrait::*;
+        match self {
+            Add | AddAssign => Add,
+            Sub | SubAssign => Sub,
+            Mul | MulAssign => Mul,
+            Div | DivAssign => Div,
+            Rem | RemAssign => Rem,
+            BitAnd | BitAndAssign => BitAnd,
+            BitOr | BitOrAssign => BitOr,
+            BitXor | BitXorAssign => BitXor,
+            Shl | ShlAssign => Shl,
+            Shr | ShrAssign => Shr,
+        }
+    }
+    fn is_commutative(self) -> bool {
+        use OpTrait::*;
+        matches!(self.to_non_assign(), Add | Mul | BitAnd | BitOr | BitXor)
+    }
+    fn is_associative(self) -> bool {
+        use OpTrait::*;
+        matches!(self.to_non_assign(), Add | Mul | BitAnd | BitOr | BitXor)
+    }
+    fn to_func_ident(self) -> Ident {
+        use OpTrait::*;
+        match self {
+            Add => format_ident!("add"),
+            AddAssign => format_ident!("add_assign"),
+            Sub => format_ident!("sub"),
+            SubAssign => format_ident!("sub_assign"),
+            Mul => format_ident!("mul"),
+            MulAssign => format_ident!("mul_assign"),
+            Div => format_ident!("div"),
+            DivAssign => format_ident!("div_assign"),
+            Rem => format_ident!("rem"),
+            RemAssign => format_ident!("rem_assign"),
+            BitAnd => format_ident!("bitand"),
+            BitAndAssign => format_ident!("bitand_assign"),
+            BitOr => format_ident!("bitor"),
+            BitOrAssign => format_ident!("bitor_assign"),
+            BitXor => format_ident!("bitxor"),
+            BitXorAssign => format_ident!("bitxor_assign"),
+            Shl => format_ident!("shl"),
+            ShlAssign => format_ident!("shl_assign"),
+            Shr => format_ident!("shr"),
+            ShrAssign => format_ident!("shr_assign"),
+        }
+    }
+}
+
+fn auto_ops_impl_inner(
+    attrs: TokenStream,
+    tokens: TokenStream,
+    instrument: bool,
+) -> Result<TokenStream> {
+    let a = Args {
+        instrument,
+        ..parse2(attrs)?
+    };
+    let i = parse2(tokens)?;
+    auto_ops_generate(a, i)
+}
+
+fn auto_ops_impl(attrs: TokenStream, tokens: TokenStream, instrument: bool) -> TokenStream {
+    auto_ops_impl_inner(attrs, tokens, instrument).unwrap_or_else(Error::into_compile_error)
+}
+
+/// semi-automatic implementation for binary operations
+///
+/// `#[auto_ops]` implements all other operations.  
+/// `#[auto_ops(assign_ref, ref_val)]` implements `T @= &U` and `&T @ U`.
+/// * `assign_ref` implements `T @= &U`.
+/// * `assign_val` implements `T @= U`.
+/// * `ref_ref` implements `&T @ &U`.
+/// * `ref_val` implements `&T @ U`.
+/// * `val_ref` implements `T @ &U`.
+/// * `val_val` implements `T @ U`.
+///
+/// Generated methods call the source by a fully-qualified path such as
+/// `<T as AddAssign<&U>>::add_assign`, so other impls of the same trait
+/// or inherent methods of the same name do not change which method is called.
+/// Other items such as `::core::ops::Add`, `::core::clone::Clone` and `::core::mem::swap`
+/// are also referred by absolute paths, so generated code works in `no_std` crates
+/// without importing them.
+/// `Self` in the trait arguments, where clauses and `type Output` of the source is replaced with `T`
+/// because `Self` is a different type in each variant.
+/// References added to operands have their own elided lifetimes,
+/// and lifetime parameters not used by a variant are removed from it.
+///
+/// `#[auto_ops(commutative)]` also implements `U @ T`, `U @ &T`, `&U @ T` and `&U @ &T`
+/// by swapping the operands.
+/// `#[auto_ops(commutative(f64, f32))]` replaces the type parameter `U` with each listed type,
+/// so that impls such as `f64 * A<f64>` do not violate the orphan rule.
+/// Bare `commutative` is rejected if `U` is a type parameter.
+/// Non-commutative operations (`-`, `/`, `%`, `<<` and `>>`) are rejected
+/// unless `force_commutative` is also given.
+/// If `T` and `U` are the same, `commutative` makes `&T @ T` as `rhs @= self`
+/// reusing the owned RHS instead of cloning `self`.
+/// This is done only for commutative operations, not for `force_commutative` or `custom`.
+///
+/// If the source is not an assign operation and its `Output` is not `T`,
+/// such as `impl Mul<&B> for &A` with `type Output = C`,
+/// assign variants are skipped, and requesting them (or `sum` and `product`) is an error.
+/// `#[auto_ops(output_into)]` implements them as `*self = (&*self * rhs).into()`
+/// with a bound `C: Into<A>`.
+/// The same applies to `MulAddAssign` variants made from `MulAdd`.
+///
+/// If the source takes `self` by value, assign variants have to move out of `&mut self`.
+/// `#[auto_ops(take = ...)]` chooses how, and the generated impl documents its panic safety.
+/// * `default` swaps `self` with `T::default()` (`T: Default`).
+///   If the operation panics, `self` is left as `T::default()`.
+///   This is the default unless `take_mut` feature is enabled.
+/// * `take_mut` works like `take_mut::take`, which aborts the process if the operation panics.
+///   This is the default with `take_mut` feature.
+/// * `replace_with` works like `replace_with::replace_with_or_abort`,
+///   which also aborts the process if the operation panics.
+/// * `placeholder(expr)` replaces `self` with `expr`, e.g. for types without a cheap `Default`.
+///   If the operation panics, `self` is left as `expr`.
+/// * `clone` calls the source with `self.clone()` (`T: Clone`).
+///   If the operation panics, `self` is unchanged.
+///
+/// `take_mut` and `replace_with` call helpers of `auto-impl-ops-runtime`, which also work in
+/// `no_std` crates without `alloc` by panicking again while unwinding.
+///
+/// Generated code refers to the helpers through `::auto_impl_ops::__private`.
+/// If `auto_ops` is used through a re-export of another crate,
+/// `#[auto_ops(crate = path)]` changes it to `path::__private`,
+/// where `path` is the re-export of the `auto_impl_ops` crate.
+///
+/// `#[auto_ops(strict)]` rejects variants which clone an operand (`T: Clone`)
+/// or move out of `&mut self` by swapping with `T::default()` (`T: Default`),
+/// with an error naming the variant.
+/// `allow_clone(ref_ref, ref_val)` allows the listed variants in `strict` mode.
+///
+/// `#[auto_ops(borrow_rhs)]` on a source with RHS `&U` makes three impls
+/// `impl<X: Borrow<U>> T @= X`, `&T @ X` and `T @ X` instead of six impls,
+/// rewriting the source to take `X` and borrow `&U` from it.
+/// The variants are selected by the usual names: `assign_ref` or `assign_val` selects `T @= X`,
+/// `ref_ref` or `ref_val` selects `&T @ X` and `val_ref` or `val_val` selects `T @ X`,
+/// and so do the names in `allow_clone(...)`.
+/// Because the impls are blanket over `X`, another impl of the same operation of `T`
+/// is accepted only for RHS types which cannot implement `Borrow<U>`, such as `T += i32`.
+/// `commutative`, `broadcast` and `lazy` would overlap with them and are rejected,
+/// as are type parameters appearing only in `U`.
+/// Two `borrow_rhs` impls of the same operation of `T` always overlap,
+/// which is reported as duplicate definitions of `borrow_rhs_Add` at `borrow_rhs`.
+///
+/// `#[auto_ops(derive_ops(Sub = Add + Neg))]` also implements all variants of `Sub`
+/// as `a - b = a + (-b)`.
+/// The form is `Target = Base + Unary`, e.g. `Div = Mul + Inv` or `Sub = BitAnd + Not`.
+/// The derived operations are generated with the same options, such as the variants and `strict`.
+/// They call `T @= &U` of `Base`, which is generated even if it is not listed in the variants
+/// when `Base` is the operation of the source, and is required by a bound otherwise.
+///
+/// `#[auto_ops(broadcast(R))]` on an element-wise source such as `A<R> @= &A<R>`
+/// also implements all variants of `A<R> @ R`.
+/// The scalar source is made by replacing each field access `rhs.field` in the source method
+/// with the scalar `rhs`, so the type of RHS of the source must be a reference.
+/// Indexing `rhs.a[i]` is also replaced with `rhs`,
+/// and `xs.zip(rhs.a.iter())` with `xs.zip(::core::iter::repeat(rhs))`,
+/// so sources over containers can be broadcast through indexing or `zip`.
+/// Other uses of `rhs`, such as nested fields `rhs.a.b` or method calls `rhs.a.len()`, are rejected.
+///
+/// `#[auto_ops(sum)]` on `Add` or `AddAssign` also implements `Sum<T>` and `Sum<&T>` for `T`,
+/// and `#[auto_ops(product)]` on `Mul` or `MulAssign` implements `Product` in the same way.
+/// They fold items with `T @= U` starting from `T::default()`,
+/// so `T @= U` and `T @= &U` are generated even if they are not listed in the variants.
+/// The initial value can be changed by `sum(zero)` (`num_traits::Zero::zero()`),
+/// `product(one)` (`num_traits::One::one()`) or any expression such as `product(A(1))`.
+///
+/// `#[auto_ops(lift)]` also defines extension traits `OptionAddExt` and `ResultAddExt`
+/// with `Some(a).add_opt(Some(&b))` and `Ok(a).add_res(Ok(&b))`, which apply `+` to the contents
+/// for any combination of operands implementing `Add`. `add_res` returns the first `Err`.
+/// The traits are implemented for all `L: Add<R>`, so they are needed only once for each operation
+/// in a module. `lift(name)` puts them into `pub mod name` to avoid duplicate definitions.
+///
+/// `#[auto_ops(lazy)]` on `T += &T` makes `&T + &T`, `&T + T`, `T + &T` and `T + T`
+/// return an expression node `AddExpr<L, R>` instead of `T`.
+/// Expression nodes also implement `+` on both sides, such as `&a + (&b + &c)`,
+/// and are evaluated with a single clone at most
+/// by `AddLazy::eval`, `T::from` or `T += expr` through the source `T += &T`.
+/// `lazy` is supported for `Add`, `Mul`, `BitAnd`, `BitOr` and `BitXor`,
+/// which are assumed to be associative.
+/// As with `lift`, `AddExpr` and `AddLazy` are defined where `lazy` is given,
+/// and `lazy(name)` puts them into `pub mod name`.
+///
+/// Traits other than `std::ops`, such as `num_traits::Pow` or aliased imports,
+/// can be declared by `custom(...)`:
+/// `#[auto_ops(custom(trait = Pow, method = pow, assign = PowAssign, assign_method = pow_assign))]`.
+/// `assign` and `assign_method` are optional and assign variants are not implemented without them.
+/// The name of the associated type can be changed by `output = Name` (default is `Output`).
+/// Custom operations are treated as non-commutative.
+///
+/// With `num-traits` feature, `#[auto_ops]` also accepts
+/// * `impl CheckedAdd for T`, which implements all variants of `T + U` panicking on overflow.
+/// * inherent methods such as `fn checked_add(&self, rhs: &Self) -> Option<Self>`,
+///   `fn wrapping_add(&self, rhs: &Self) -> Self` or `fn saturating_add(&self, rhs: &Self) -> Self`,
+///   which implement `num_traits::CheckedAdd`, `num_traits::WrappingAdd` or `num_traits::SaturatingAdd`.
+///   Checked methods also implement all variants of `T + U` panicking on overflow.
+///   Wrapping and saturating methods implement no `std::ops` operations, but their traits require
+///   `T: Add<Output = T>`, which is implemented by the checked method in the same impl or separately.
+///   If it is missing, the error is reported at the method.
+/// * `impl MulAddAssign<&A, &B> for T` or `impl MulAdd<&A, &B> for &T`,
+///   which implements `MulAddAssign` and `MulAdd` for all combinations of `T`, `A` and `B`
+///   by reference and by value.
+///   Variants are named such as `assign_ref_val` (`T.mul_add_assign(&A, B)`)
+///   or `val_ref_ref` (`T.mul_add(&A, &B)`).
+///
+/// `Add`, `Sub`, `Mul`, `Div` and `Rem` (only checked) are supported.
+///
+/// For comparisons, `#[auto_ops]` on `impl PartialEq<U> for T` implements
+/// `PartialEq<&U> for T` (`val_ref`), `PartialEq<U> for &T` (`ref_val`)
+/// and the mirrored impls `rev_val_val`, `rev_val_ref` and `rev_ref_val` if `T` and `U` differ.
+/// `PartialOrd` is implemented in the same way, reversing the ordering of the mirrored impls.
+/// `&T == &U` is already implemented by `std`.
+///
+/// If the source method has `#[track_caller]`, all generated methods also have it,
+/// so that a panic in the source method reports the location of `&a / &b` in the caller.
+/// `#[auto_ops(track_caller)]` adds it to the source method and all generated methods.
+/// For checked sources, the generated `T + U` panicking on overflow report the caller in the same way.
+///
+/// For unary operations (`Neg`, `Not` and `num_traits::Inv`)
+/// `#[auto_ops(ref)]` implements `@&T` and `#[auto_ops(val)]` implements `@T`.
+///
+/// With `instrument` feature, each generated method counts its calls, clones of operands
+/// and `Default` swaps in a static counter of `auto-impl-ops-runtime`.
+/// `auto_impl_ops::snapshot()` returns the counters by type and variant
+/// (`rev_ref_val` and so on for mirrored impls), e.g. to find hot paths that clone,
+/// and `auto_impl_ops::reset()` sets them to zero.
+/// Types are named by `core::any::type_name`, so instances of a generic impl
+/// such as `A<i32>` and `A<f64>` are counted separately.
+/// The counters require `alloc`, which is not used without `instrument` feature.
+#[proc_macro_attribute]
+pub fn auto_ops(
+    attrs: proc_macro::TokenStream,
+    tokens: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    auto_ops_impl(attrs.into(), tokens.into(), cfg!(feature = "instrument")).into()
+}
+
+/// field-wise implementation for binary operations
+///
+/// `#[ops(Add, Mul)]` implements `T += &T` and `T *= &T` by applying the operation to each field,
+/// then implements all other operations as `#[auto_ops]` does.
+/// Bounds such as `F: for<'x> AddAssign<&'x F>` are added for each field type `F`.
+/// * `PhantomData` fields are skipped.
+/// * `#[ops(skip)]` on a field skips the field for all operations.
+/// * `#[ops(skip(Mul))]` on a field skips the field for the listed operations.
+///
+/// See the README of `auto-impl-ops` for an example.
+#[proc_macro_derive(AutoOps, attributes(ops))]
+pub fn derive_auto_ops(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    derive::derive_auto_ops_impl(tokens.into(), cfg!(feature = "instrument")).into()
+}
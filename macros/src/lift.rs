@@ -0,0 +1,74 @@
+use super::*;
+
+/// Makes `OptionAddExt` and `ResultAddExt`, which are implemented for any `L: Add<R>`.
+fn gen_traits(op: OpTrait) -> TokenStream {
+    let option_trait = format_ident!("Option{}Ext", op.to_string());
+    let result_trait = format_ident!("Result{}Ext", op.to_string());
+    let fn_name = op.to_func_ident();
+    let option_fn_name = format_ident!("{}_opt", fn_name);
+    let result_fn_name = format_ident!("{}_res", fn_name);
+    quote! {
+        pub trait #option_trait<Rhs> {
+            type Output;
+            fn #option_fn_name(
+                self,
+                rhs: ::core::option::Option<Rhs>,
+            ) -> ::core::option::Option<Self::Output>;
+        }
+        impl<L: #op<R>, R> #option_trait<R> for ::core::option::Option<L> {
+            type Output = <L as #op<R>>::Output;
+            fn #option_fn_name(
+                self,
+                rhs: ::core::option::Option<R>,
+            ) -> ::core::option::Option<Self::Output> {
+                match (self, rhs) {
+                    (::core::option::Option::Some(lhs), ::core::option::Option::Some(rhs)) => {
+                        ::core::option::Option::Some(<L as #op<R>>::#fn_name(lhs, rhs))
+                    }
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+        pub trait #result_trait<Rhs, E> {
+            type Output;
+            fn #result_fn_name(
+                self,
+                rhs: ::core::result::Result<Rhs, E>,
+            ) -> ::core::result::Result<Self::Output, E>;
+        }
+        impl<L: #op<R>, R, E> #result_trait<R, E> for ::core::result::Result<L, E> {
+            type Output = <L as #op<R>>::Output;
+            fn #result_fn_name(
+                self,
+                rhs: ::core::result::Result<R, E>,
+            ) -> ::core::result::Result<Self::Output, E> {
+                ::core::result::Result::Ok(<L as #op<R>>::#fn_name(self?, rhs?))
+            }
+        }
+    }
+}
+
+/// Generates extension traits lifting the operation over `Option` and `Result`.
+pub(crate) fn generate_lift(
+    key: &Ident,
+    module: Option<&Ident>,
+    family: &OpFamily,
+) -> Result<TokenStream> {
+    let op = family.op.ok_or_else(|| {
+        Error::new(
+            key.span(),
+            format!("`{}` is not supported for `custom` operations", key),
+        )
+    })?;
+    let traits = gen_traits(op.to_non_assign());
+    if let Some(module) = module {
+        Ok(quote! {
+            pub mod #module {
+                use super::*;
+                #traits
+            }
+        })
+    } else {
+        Ok(traits)
+    }
+}
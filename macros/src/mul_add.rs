@@ -0,0 +1,279 @@
+use super::*;
+
+/// `MulAddOperate(is_assign, is_self_ref, is_a_ref, is_b_ref)`
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+struct MulAddOperate(bool, bool, bool, bool);
+impl MulAddOperate {
+    /// Projects onto the binary `Operate` ignoring the operands.
+    fn lhs(self) -> Operate {
+        Operate(self.0, self.1, false)
+    }
+    fn name(&self) -> String {
+        let ref_val = |x: bool| if x { "ref" } else { "val" };
+        let first = if self.0 { "assign" } else { ref_val(self.1) };
+        format!("{}_{}_{}", first, ref_val(self.2), ref_val(self.3))
+    }
+}
+
+/// Returns `(A, B)` of `MulAdd<A, B>`, which default to `Self`.
+fn get_operand_types<'a>(
+    args: &'a PathArguments,
+    self_type: &'a Type,
+) -> Result<(&'a Type, &'a Type)> {
+    match args {
+        PathArguments::None => Ok((self_type, self_type)),
+        PathArguments::AngleBracketed(args) => {
+            let args = &args.args;
+            if args.len() != 2 {
+                return Err(Error::new(
+                    args.span(),
+                    "Number of trait arguments is not 2",
+                ));
+            }
+            let mut types = args.iter().map(|x| {
+                if let GenericArgument::Type(type_) = x {
+                    Ok(type_)
+                } else {
+                    Err(Error::new(x.span(), "Is not type"))
+                }
+            });
+            Ok((types.next().unwrap()?, types.next().unwrap()?))
+        }
+        _ => Err(Error::new(args.span(), "Unexpected trait arguments")),
+    }
+}
+
+/// Makes `let a = &a;` or `let a = a.clone();` if required.
+fn gen_operand(name: &Ident, source_ref: bool, op_ref: bool) -> TokenStream {
+    match (source_ref, op_ref) {
+        (true, false) => quote!(let #name = &#name;),
+        (false, true) => quote!(let #name = ::core::clone::Clone::clone(#name);),
+        _ => TokenStream::new(),
+    }
+}
+
+struct MulAddGenerator<'a> {
+    implement: &'a ItemImpl,
+    source_op: MulAddOperate,
+    self_type: &'a Type,
+    a_type: &'a Type,
+    b_type: &'a Type,
+    take: &'a TakeStrategy,
+    private: &'a Path,
+    /// whether generated methods have `#[track_caller]`
+    track_caller: bool,
+    /// `Output` of the source if it is not `T`
+    other_output: Option<Type>,
+    /// whether `output_into` is given
+    output_into: bool,
+    /// whether generated methods count calls
+    instrument: bool,
+}
+impl MulAddGenerator<'_> {
+    fn update_where_clause(&self, generics: &mut Generics, op: MulAddOperate) {
+        let source_op = self.source_op;
+        let clone_types = [
+            (
+                source_op.lhs().require_lhs_clone(op.lhs()),
+                remove_reference(self.self_type),
+            ),
+            (!source_op.2 && op.2, remove_reference(self.a_type)),
+            (!source_op.3 && op.3, remove_reference(self.b_type)),
+        ];
+        self.assign_source()
+            .update_where_clause(generics, &clone_types, op.0);
+    }
+    /// Returns `<T as MulAddAssign<&A, &B>>::mul_add_assign` or `<&T as MulAdd<&A, &B>>::mul_add`.
+    fn source_method(&self) -> ExprPath {
+        if self.source_op.0 {
+            qualified_method(self.implement, &format_ident!("mul_add_assign"))
+        } else {
+            qualified_method(self.implement, &format_ident!("mul_add"))
+        }
+    }
+    fn assign_source(&self) -> AssignSource<'_> {
+        let (a, b) = (local("a"), local("b"));
+        AssignSource {
+            method: self.source_method(),
+            operands: quote!(#a, #b),
+            source_op: self.source_op.lhs(),
+            self_type: remove_reference(self.self_type),
+            take: self.take,
+            private: self.private,
+            other_output: &self.other_output,
+            output_into: self.output_into,
+        }
+    }
+    fn gen_output(&self) -> Result<Type> {
+        if self.source_op.0 {
+            Ok(remove_reference(self.self_type).clone())
+        } else {
+            get_output_type(self.implement, &format_ident!("Output"))
+        }
+    }
+    fn generate(&self, op: MulAddOperate) -> Result<TokenStream> {
+        if op == self.source_op {
+            return Ok(self.implement.to_token_stream());
+        }
+        let mut work = self.implement.clone();
+        let a_type = Generator::get_arg_type(op.2, self.a_type);
+        let b_type = Generator::get_arg_type(op.3, self.b_type);
+        let trait_ = if op.0 {
+            format_ident!("MulAddAssign")
+        } else {
+            format_ident!("MulAdd")
+        };
+        work.trait_.as_mut().unwrap().1 = parse_quote! { ::num_traits::#trait_<#a_type, #b_type> };
+        *work.self_ty.as_mut() = Generator::get_arg_type(op.1, self.self_type);
+        self.update_where_clause(&mut work.generics, op);
+        work.items.clear();
+        let (a, b) = (local("a"), local("b"));
+        let preamble_a = gen_operand(&a, self.source_op.2, op.2);
+        let preamble_b = gen_operand(&b, self.source_op.3, op.3);
+        let source_op = self.source_op;
+        let take = source_op.lhs().lhs_move() && op.0;
+        let (take_clones, defaults) = if take { self.take.counts() } else { (0, 0) };
+        let clones = source_op.lhs().require_lhs_clone(op.lhs()) as u64
+            + (!source_op.2 && op.2) as u64
+            + (!source_op.3 && op.3) as u64
+            + take_clones;
+        let instrument = gen_instrument(
+            self.instrument,
+            self.private,
+            self.self_type,
+            &op.name(),
+            clones,
+            defaults,
+        );
+        let track_caller = if self.track_caller {
+            quote!(#[track_caller])
+        } else {
+            TokenStream::new()
+        };
+        if op.0 {
+            if take {
+                work.attrs.extend(self.take.gen_doc());
+            }
+            let body = self.assign_source().gen_body();
+            work.items.push(parse_quote! {
+                #track_caller
+                fn mul_add_assign(&mut self, #a: #a_type, #b: #b_type) {
+                    #instrument
+                    #preamble_a
+                    #preamble_b
+                    #body
+                }
+            });
+        } else {
+            let output_type = self.gen_output()?;
+            work.items.push(parse_quote! {
+                type Output = #output_type;
+            });
+            let preamble_lhs = Generator::gen_lhs(self.source_op.lhs(), op.lhs());
+            let source_method = self.source_method();
+            let lhs = local("lhs");
+            let body = if self.source_op.0 {
+                quote! {
+                    #source_method(&mut #lhs, #a, #b);
+                    #lhs
+                }
+            } else {
+                quote! {
+                    #source_method(#lhs, #a, #b)
+                }
+            };
+            work.items.push(parse_quote! {
+                #track_caller
+                fn mul_add(self, #a: #a_type, #b: #b_type) -> Self::Output {
+                    #instrument
+                    #preamble_lhs
+                    #preamble_a
+                    #preamble_b
+                    #body
+                }
+            });
+        }
+        drop_unused_lifetimes(&mut work);
+        Ok(quote!(#work))
+    }
+}
+
+/// Generates all variants of `T.mul_add(A, B)` and `T.mul_add_assign(A, B)`
+/// from `impl MulAddAssign<&A, &B> for T` or `impl MulAdd<&A, &B> for &T`.
+pub(crate) fn auto_ops_generate_mul_add(
+    attrs: &Attributes,
+    implement: &ItemImpl,
+    is_assign: bool,
+    take: &TakeStrategy,
+    output_into: bool,
+    private: &Path,
+    instrument: bool,
+) -> Result<TokenStream> {
+    let last_segment = get_last_segment(implement)?;
+    let self_type = &implement.self_ty;
+    let (a_type, b_type) = get_operand_types(&last_segment.arguments, self_type)?;
+    if is_assign && is_ref(self_type) {
+        return Err(Error::new(
+            self_type.span(),
+            "Type of `Self` must not reference",
+        ));
+    }
+    let other_output = if is_assign {
+        None
+    } else {
+        Some(get_output_type(implement, &format_ident!("Output"))?)
+            .filter(|output| output != remove_reference(self_type))
+    };
+    let generator = MulAddGenerator {
+        implement,
+        source_op: MulAddOperate(is_assign, is_ref(self_type), is_ref(a_type), is_ref(b_type)),
+        self_type,
+        a_type,
+        b_type,
+        take,
+        private,
+        track_caller: has_track_caller(implement),
+        other_output,
+        output_into,
+        instrument,
+    };
+    let mut list = Vec::new();
+    for is_a_ref in [true, false] {
+        for is_b_ref in [true, false] {
+            list.push(MulAddOperate(true, false, is_a_ref, is_b_ref));
+        }
+    }
+    for is_self_ref in [true, false] {
+        for is_a_ref in [true, false] {
+            for is_b_ref in [true, false] {
+                list.push(MulAddOperate(false, is_self_ref, is_a_ref, is_b_ref));
+            }
+        }
+    }
+    let map = list
+        .iter()
+        .map(|op| (op.name(), *op))
+        .collect::<HashMap<_, _>>();
+    let mut attrs = attrs.clone();
+    if attrs.is_empty() {
+        attrs = list
+            .iter()
+            .filter(|x| !x.0 || generator.assign_source().available())
+            .map(|x| format_ident!("{}", x.name()))
+            .collect();
+    }
+    let source = generator.source_op.name();
+    if !attrs.iter().any(|x| *x == source) {
+        attrs.push(format_ident!("{}", source));
+    }
+    let mut result = TokenStream::new();
+    for i in attrs.iter() {
+        if let Some(op) = map.get(&i.to_string()) {
+            if op.0 && !generator.assign_source().available() {
+                return Err(output_error(i, self_type, &generator.other_output));
+            }
+            result.extend(generator.generate(*op)?);
+        }
+    }
+    Ok(result)
+}
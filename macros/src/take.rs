@@ -0,0 +1,152 @@
+use super::*;
+use syn::parse::{Parse, ParseStream};
+
+/// How an assign variant moves out of `&mut self` when the source consumes `self`.
+#[derive(Clone, Debug)]
+pub(crate) enum TakeStrategy {
+    /// Swaps with `T::default()`.
+    Default,
+    /// `take` of `auto-impl-ops-runtime`, which works like `take_mut::take`
+    TakeMut,
+    /// `replace_with_or_abort` of `auto-impl-ops-runtime`
+    ReplaceWith,
+    /// Replaces with the given expression.
+    Placeholder(Box<Expr>),
+    /// Calls the source with a clone of `self`.
+    Clone,
+}
+impl Parse for TakeStrategy {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let ident = input.call(Ident::parse_any)?;
+        match ident.to_string().as_str() {
+            "default" => Ok(Self::Default),
+            "take_mut" => Ok(Self::TakeMut),
+            "replace_with" => Ok(Self::ReplaceWith),
+            "clone" => Ok(Self::Clone),
+            "placeholder" => {
+                let content;
+                parenthesized!(content in input);
+                Ok(Self::Placeholder(Box::new(content.parse()?)))
+            }
+            _ => Err(Error::new(
+                ident.span(),
+                format!("unknown strategy of `take`: {}", ident),
+            )),
+        }
+    }
+}
+impl TakeStrategy {
+    /// Returns the strategy given by `take = ...`, which defaults to `take_mut`
+    /// with `take_mut` feature and `default` otherwise.
+    pub(crate) fn from_arg(take: &Option<(Ident, TakeStrategy)>) -> Self {
+        match take {
+            Some((_, strategy)) => strategy.clone(),
+            None if cfg!(feature = "take_mut") => Self::TakeMut,
+            None => Self::Default,
+        }
+    }
+    /// Returns why the strategy requires `Clone` or `Default`, if it does.
+    pub(crate) fn fallback_reason(&self, self_type: &Type) -> Option<String> {
+        let self_type = self_type.to_token_stream();
+        match self {
+            Self::Default => Some(format!(
+                "it moves out of `&mut self` by swapping with `{}::default()`",
+                self_type
+            )),
+            Self::Clone => Some(format!(
+                "it clones LHS `{}` instead of moving out of `&mut self`",
+                self_type
+            )),
+            _ => None,
+        }
+    }
+    /// Returns the number of clones and `Default` swaps made by the strategy.
+    pub(crate) fn counts(&self) -> (u64, u64) {
+        match self {
+            Self::Default => (0, 1),
+            Self::Clone => (1, 0),
+            _ => (0, 0),
+        }
+    }
+    /// Returns `#[doc = "..."]` describing the panic safety of the strategy.
+    pub(crate) fn gen_doc(&self) -> Vec<Attribute> {
+        let (how, panic) = match self {
+            Self::Default => (
+                "by swapping it with `Self::default()`".to_string(),
+                "`self` is left as `Self::default()`".to_string(),
+            ),
+            Self::TakeMut => (
+                "like `take_mut::take`".to_string(),
+                "the process is aborted".to_string(),
+            ),
+            Self::ReplaceWith => (
+                "like `replace_with::replace_with_or_abort`".to_string(),
+                "the process is aborted".to_string(),
+            ),
+            Self::Placeholder(expr) => {
+                let expr = expr.to_token_stream();
+                (
+                    format!("by replacing it with `{}`", expr),
+                    format!("`self` is left as `{}`", expr),
+                )
+            }
+            Self::Clone => (
+                "by cloning it".to_string(),
+                "`self` is unchanged".to_string(),
+            ),
+        };
+        let lines = [
+            format!(" Moves out of `self` {}.", how),
+            format!(" If the operation panics, {}.", panic),
+        ];
+        lines.iter().map(|x| parse_quote!(#[doc = #x])).collect()
+    }
+    /// Makes statements assigning `call(x)` to `*self`, where `x` is moved out of `self`.
+    pub(crate) fn gen_body(
+        &self,
+        private: &Path,
+        call: impl Fn(TokenStream) -> TokenStream,
+    ) -> TokenStream {
+        match self {
+            Self::Default => {
+                let t = local("t");
+                let u = local("u");
+                let value = call(quote!(#t));
+                quote! {
+                    let mut #t = <Self as ::core::default::Default>::default();
+                    ::core::mem::swap(&mut #t, self);
+                    let mut #u = #value;
+                    ::core::mem::swap(&mut #u, self);
+                }
+            }
+            Self::TakeMut => {
+                let x = local("x");
+                let value = call(quote!(#x));
+                quote! {
+                    #private::take(self, |#x| #value);
+                }
+            }
+            Self::ReplaceWith => {
+                let x = local("x");
+                let value = call(quote!(#x));
+                quote! {
+                    #private::replace_with_or_abort(self, |#x| #value);
+                }
+            }
+            Self::Placeholder(expr) => {
+                let t = local("t");
+                let value = call(quote!(#t));
+                quote! {
+                    let #t = ::core::mem::replace(self, #expr);
+                    *self = #value;
+                }
+            }
+            Self::Clone => {
+                let value = call(quote!(::core::clone::Clone::clone(self)));
+                quote! {
+                    *self = #value;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,3621 @@
+use super::*;
+fn to_pretty_string(tokens: proc_macro2::TokenStream) -> String {
+    prettyplease::unparse(&syn::parse2(tokens).unwrap())
+}
+/// Expands without counters, so that expansions do not depend on `instrument` feature.
+fn auto_ops_impl(attrs: TokenStream, tokens: TokenStream) -> TokenStream {
+    super::auto_ops_impl(attrs, tokens, false)
+}
+macro_rules! assert_eq {
+    ($expected:expr, $result:expr) => {
+        pretty_assertions::assert_eq!(to_pretty_string($expected), to_pretty_string($result));
+    };
+}
+
+#[test]
+fn unsupported_trait() {
+    assert_eq! {
+        auto_ops_impl(
+            TokenStream::new(),
+        quote!{
+            impl<T> Clone for A<T>
+            where T: Clone{
+                fn clone(&self) -> Self {
+                    Self(self.0.clone())
+                }
+            }
+        }),
+        quote!{
+            compile_error!{ "unexpacted Ident: Clone" }
+        }
+    };
+}
+
+#[test]
+fn add_assign1() {
+    assert_eq! {
+        auto_ops_impl(
+            TokenStream::new(),
+            quote! {
+                impl<'a, M> AddAssign<&'a A<M>> for A<M>
+                where
+                    M: Sized + Zero + for<'x> AddAssign<&'x M>,
+                {
+                    fn add_assign(&mut self, other: &Self) {
+                        self.0 += &other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl<'a, M> AddAssign<&'a A<M>> for A<M>
+            where
+                M: Sized + Zero + for<'x> AddAssign<&'x M>,
+            {
+                fn add_assign(&mut self, other: &Self) {
+                    self.0 += &other.0;
+                }
+            }
+            impl<M> ::core::ops::AddAssign<A<M>> for A<M>
+            where
+                M: Sized + Zero + for<'x> AddAssign<&'x M>,
+            {
+                fn add_assign(&mut self, rhs: A<M>) {
+                    let rhs = &rhs;
+                    <A<M> as AddAssign<&'_ A<M>>>::add_assign(self, rhs);
+                }
+            }
+            impl<'a, M> ::core::ops::Add<&'a A<M>> for &A<M>
+            where
+                M: Sized + Zero + for<'x> AddAssign<&'x M>,
+                A<M>: ::core::clone::Clone,
+            {
+                type Output = A<M>;
+                fn add(self, rhs: &'a A<M>) -> Self::Output {
+                    let mut lhs = ::core::clone::Clone::clone(self);
+                    <A<M> as AddAssign<&'_ A<M>>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl<M> ::core::ops::Add<A<M>> for &A<M>
+            where
+                M: Sized + Zero + for<'x> AddAssign<&'x M>,
+                A<M>: ::core::clone::Clone,
+            {
+                type Output = A<M>;
+                fn add(self, rhs: A<M>) -> Self::Output {
+                    let mut lhs = ::core::clone::Clone::clone(self);
+                    let rhs = &rhs;
+                    <A<M> as AddAssign<&'_ A<M>>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl<'a, M> ::core::ops::Add<&'a A<M>> for A<M>
+            where
+                M: Sized + Zero + for<'x> AddAssign<&'x M>,
+            {
+                type Output = A<M>;
+                fn add(self, rhs: &'a A<M>) -> Self::Output {
+                    let mut lhs = self;
+                    <A<M> as AddAssign<&'_ A<M>>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl<M> ::core::ops::Add<A<M>> for A<M>
+            where
+                M: Sized + Zero + for<'x> AddAssign<&'x M>,
+            {
+                type Output = A<M>;
+                fn add(self, rhs: A<M>) -> Self::Output {
+                    let mut lhs = self;
+                    let rhs = &rhs;
+                    <A<M> as AddAssign<&'_ A<M>>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+        }
+    };
+}
+#[test]
+fn add_assign2() {
+    assert_eq! {
+        auto_ops_impl(
+            TokenStream::new(),
+            quote! {
+                impl<'a> AddAssign<&'a B> for B {
+                    fn add_assign(&mut self, other: &Self) {
+                        self.0 += &other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl<'a> AddAssign<&'a B> for B {
+                fn add_assign(&mut self, other: &Self) {
+                    self.0 += &other.0;
+                }
+            }
+            impl ::core::ops::AddAssign<B> for B {
+                fn add_assign(&mut self, rhs: B) {
+                    let rhs = &rhs;
+                    <B as AddAssign<&'_ B>>::add_assign(self, rhs);
+                }
+            }
+            impl<'a> ::core::ops::Add<&'a B> for &B
+            where
+                B: ::core::clone::Clone,
+            {
+                type Output = B;
+                fn add(self, rhs: &'a B) -> Self::Output {
+                    let mut lhs = ::core::clone::Clone::clone(self);
+                    <B as AddAssign<&'_ B>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl ::core::ops::Add<B> for &B
+            where
+                B: ::core::clone::Clone,
+            {
+                type Output = B;
+                fn add(self, rhs: B) -> Self::Output {
+                    let mut lhs = ::core::clone::Clone::clone(self);
+                    let rhs = &rhs;
+                    <B as AddAssign<&'_ B>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl<'a> ::core::ops::Add<&'a B> for B {
+                type Output = B;
+                fn add(self, rhs: &'a B) -> Self::Output {
+                    let mut lhs = self;
+                    <B as AddAssign<&'_ B>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl ::core::ops::Add<B> for B {
+                type Output = B;
+                fn add(self, rhs: B) -> Self::Output {
+                    let mut lhs = self;
+                    let rhs = &rhs;
+                    <B as AddAssign<&'_ B>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+        }
+    };
+}
+#[test]
+fn mul() {
+    assert_eq! {
+        auto_ops_impl(
+            TokenStream::new(),
+            quote! {
+                impl<'a, M> Mul for &'a A<M>
+                where
+                    M: Sized + Zero,
+                    for<'x> &'x M: Mul<Output = M>,
+                {
+                    type Output = A<M>;
+                    fn mul(self, other: Self) -> Self::Output {
+                        A(&self.0 * &other.0)
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl<'a, M> ::core::ops::MulAssign<&'a A<M>> for A<M>
+            where
+                M: Sized + Zero,
+                for<'x> &'x M: Mul<Output = M>,
+            {
+                fn mul_assign(&mut self, rhs: &'a A<M>) {
+                    *self = <&'_ A<M> as Mul>::mul(&*self, rhs);
+                }
+            }
+            impl<M> ::core::ops::MulAssign<A<M>> for A<M>
+            where
+                M: Sized + Zero,
+                for<'x> &'x M: Mul<Output = M>,
+            {
+                fn mul_assign(&mut self, rhs: A<M>) {
+                    let rhs = &rhs;
+                    *self = <&'_ A<M> as Mul>::mul(&*self, rhs);
+                }
+            }
+            impl<'a, M> Mul for &'a A<M>
+            where
+                M: Sized + Zero,
+                for<'x> &'x M: Mul<Output = M>,
+            {
+                type Output = A<M>;
+                fn mul(self, other: Self) -> Self::Output {
+                    A(&self.0 * &other.0)
+                }
+            }
+            impl<'a, M> ::core::ops::Mul<A<M>> for &'a A<M>
+            where
+                M: Sized + Zero,
+                for<'x> &'x M: Mul<Output = M>,
+            {
+                type Output = A<M>;
+                fn mul(self, rhs: A<M>) -> Self::Output {
+                    let lhs = self;
+                    let rhs = &rhs;
+                    <&'_ A<M> as Mul>::mul(lhs, rhs)
+                }
+            }
+            impl<'a, M> ::core::ops::Mul<&'a A<M>> for A<M>
+            where
+                M: Sized + Zero,
+                for<'x> &'x M: Mul<Output = M>,
+            {
+                type Output = A<M>;
+                fn mul(self, rhs: &'a A<M>) -> Self::Output {
+                    let lhs = &self;
+                    <&'_ A<M> as Mul>::mul(lhs, rhs)
+                }
+            }
+            impl<M> ::core::ops::Mul<A<M>> for A<M>
+            where
+                M: Sized + Zero,
+                for<'x> &'x M: Mul<Output = M>,
+            {
+                type Output = A<M>;
+                fn mul(self, rhs: A<M>) -> Self::Output {
+                    let lhs = &self;
+                    let rhs = &rhs;
+                    <&'_ A<M> as Mul>::mul(lhs, rhs)
+                }
+            }
+        }
+    };
+}
+
+#[test]
+#[cfg(not(feature = "take_mut"))]
+fn div() {
+    assert_eq! {
+        auto_ops_impl(
+            TokenStream::new(),
+            quote! {
+                impl<'a, M> Div<&'a A<M>> for A<M>
+                where
+                    M: Sized + Zero,
+                    for<'x> &'x M: Div<Output = M>,
+                {
+                    type Output = Self;
+                    fn div(self, other: &Self) -> Self::Output {
+                        A(&self.0 / &other.0)
+                    }
+                }
+            },
+        ),
+        quote!{
+            /// Moves out of `self` by swapping it with `Self::default()`.
+            /// If the operation panics, `self` is left as `Self::default()`.
+            impl<'a, M> ::core::ops::DivAssign<&'a A<M>> for A<M>
+            where
+                M: Sized + Zero,
+                for<'x> &'x M: Div<Output = M>,
+                A<M>: ::core::default::Default,
+            {
+                fn div_assign(&mut self, rhs: &'a A<M>) {
+                    let mut t = <Self as ::core::default::Default>::default();
+                    ::core::mem::swap(&mut t, self);
+                    let mut u = <A<M> as Div<&'_ A<M>>>::div(t, rhs);
+                    ::core::mem::swap(&mut u, self);
+                }
+            }
+            /// Moves out of `self` by swapping it with `Self::default()`.
+            /// If the operation panics, `self` is left as `Self::default()`.
+            impl<M> ::core::ops::DivAssign<A<M>> for A<M>
+            where
+                M: Sized + Zero,
+                for<'x> &'x M: Div<Output = M>,
+                A<M>: ::core::default::Default,
+            {
+                fn div_assign(&mut self, rhs: A<M>) {
+                    let rhs = &rhs;
+                    let mut t = <Self as ::core::default::Default>::default();
+                    ::core::mem::swap(&mut t, self);
+                    let mut u = <A<M> as Div<&'_ A<M>>>::div(t, rhs);
+                    ::core::mem::swap(&mut u, self);
+                }
+            }
+            impl<'a, M> ::core::ops::Div<&'a A<M>> for &A<M>
+            where
+                M: Sized + Zero,
+                for<'x> &'x M: Div<Output = M>,
+                A<M>: ::core::clone::Clone,
+            {
+                type Output = A<M>;
+                fn div(self, rhs: &'a A<M>) -> Self::Output {
+                    let lhs = ::core::clone::Clone::clone(self);
+                    <A<M> as Div<&'_ A<M>>>::div(lhs, rhs)
+                }
+            }
+            impl<M> ::core::ops::Div<A<M>> for &A<M>
+            where
+                M: Sized + Zero,
+                for<'x> &'x M: Div<Output = M>,
+                A<M>: ::core::clone::Clone,
+            {
+                type Output = A<M>;
+                fn div(self, rhs: A<M>) -> Self::Output {
+                    let lhs = ::core::clone::Clone::clone(self);
+                    let rhs = &rhs;
+                    <A<M> as Div<&'_ A<M>>>::div(lhs, rhs)
+                }
+            }
+            impl<'a, M> Div<&'a A<M>> for A<M>
+            where
+                M: Sized + Zero,
+                for<'x> &'x M: Div<Output = M>,
+            {
+                type Output = A<M>;
+                fn div(self, other: &Self) -> Self::Output {
+                    A(&self.0 / &other.0)
+                }
+            }
+            impl<M> ::core::ops::Div<A<M>> for A<M>
+            where
+                M: Sized + Zero,
+                for<'x> &'x M: Div<Output = M>,
+            {
+                type Output = A<M>;
+                fn div(self, rhs: A<M>) -> Self::Output {
+                    let lhs = self;
+                    let rhs = &rhs;
+                    <A<M> as Div<&'_ A<M>>>::div(lhs, rhs)
+                }
+            }
+        }
+    };
+}
+
+#[test]
+#[cfg(feature = "take_mut")]
+fn div() {
+    assert_eq! {
+        auto_ops_impl(
+            TokenStream::new(),
+            quote! {
+                impl<'a, M> Div<&'a A<M>> for A<M>
+                where
+                    M: Sized + Zero,
+                    for<'x> &'x M: Div<Output = M>,
+                {
+                    type Output = Self;
+                    fn div(self, other: &Self) -> Self::Output {
+                        A(&self.0 / &other.0)
+                    }
+                }
+            },
+        ),
+        quote!{
+            /// Moves out of `self` like `take_mut::take`.
+            /// If the operation panics, the process is aborted.
+            impl<'a, M> ::core::ops::DivAssign<&'a A<M>> for A<M>
+            where
+                M: Sized + Zero,
+                for<'x> &'x M: Div<Output = M>,
+            {
+                fn div_assign(&mut self, rhs: &'a A<M>) {
+                    ::auto_impl_ops::__private::take(self, |x| <A<M> as Div<&'_ A<M>>>::div(x, rhs));
+                }
+            }
+            /// Moves out of `self` like `take_mut::take`.
+            /// If the operation panics, the process is aborted.
+            impl<M> ::core::ops::DivAssign<A<M>> for A<M>
+            where
+                M: Sized + Zero,
+                for<'x> &'x M: Div<Output = M>,
+            {
+                fn div_assign(&mut self, rhs: A<M>) {
+                    let rhs = &rhs;
+                    ::auto_impl_ops::__private::take(self, |x| <A<M> as Div<&'_ A<M>>>::div(x, rhs));
+                }
+            }
+            impl<'a, M> ::core::ops::Div<&'a A<M>> for &A<M>
+            where
+                M: Sized + Zero,
+                for<'x> &'x M: Div<Output = M>,
+                A<M>: ::core::clone::Clone,
+            {
+                type Output = A<M>;
+                fn div(self, rhs: &'a A<M>) -> Self::Output {
+                    let lhs = ::core::clone::Clone::clone(self);
+                    <A<M> as Div<&'_ A<M>>>::div(lhs, rhs)
+                }
+            }
+            impl<M> ::core::ops::Div<A<M>> for &A<M>
+            where
+                M: Sized + Zero,
+                for<'x> &'x M: Div<Output = M>,
+                A<M>: ::core::clone::Clone,
+            {
+                type Output = A<M>;
+                fn div(self, rhs: A<M>) -> Self::Output {
+                    let lhs = ::core::clone::Clone::clone(self);
+                    let rhs = &rhs;
+                    <A<M> as Div<&'_ A<M>>>::div(lhs, rhs)
+                }
+            }
+            impl<'a, M> Div<&'a A<M>> for A<M>
+            where
+                M: Sized + Zero,
+                for<'x> &'x M: Div<Output = M>,
+            {
+                type Output = A<M>;
+                fn div(self, other: &Self) -> Self::Output {
+                    A(&self.0 / &other.0)
+                }
+            }
+            impl<M> ::core::ops::Div<A<M>> for A<M>
+            where
+                M: Sized + Zero,
+                for<'x> &'x M: Div<Output = M>,
+            {
+                type Output = A<M>;
+                fn div(self, rhs: A<M>) -> Self::Output {
+                    let lhs = self;
+                    let rhs = &rhs;
+                    <A<M> as Div<&'_ A<M>>>::div(lhs, rhs)
+                }
+            }
+        }
+    };
+}
+
+#[test]
+fn add_assign_no_commma() {
+    assert_eq! {
+        auto_ops_impl(
+            TokenStream::new(),
+            quote! {
+                impl<'a, M> AddAssign<&'a A<M>> for A<M>
+                where
+                    M: Sized + Zero + for<'x> AddAssign<&'x M>
+                {
+                    fn add_assign(&mut self, other: &Self) {
+                        self.0 += &other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl<'a, M> AddAssign<&'a A<M>> for A<M>
+            where
+                M: Sized + Zero + for<'x> AddAssign<&'x M>,
+            {
+                fn add_assign(&mut self, other: &Self) {
+                    self.0 += &other.0;
+                }
+            }
+            impl<M> ::core::ops::AddAssign<A<M>> for A<M>
+            where
+                M: Sized + Zero + for<'x> AddAssign<&'x M>,
+            {
+                fn add_assign(&mut self, rhs: A<M>) {
+                    let rhs = &rhs;
+                    <A<M> as AddAssign<&'_ A<M>>>::add_assign(self, rhs);
+                }
+            }
+            impl<'a, M> ::core::ops::Add<&'a A<M>> for &A<M>
+            where
+                M: Sized + Zero + for<'x> AddAssign<&'x M>,
+                A<M>: ::core::clone::Clone,
+            {
+                type Output = A<M>;
+                fn add(self, rhs: &'a A<M>) -> Self::Output {
+                    let mut lhs = ::core::clone::Clone::clone(self);
+                    <A<M> as AddAssign<&'_ A<M>>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl<M> ::core::ops::Add<A<M>> for &A<M>
+            where
+                M: Sized + Zero + for<'x> AddAssign<&'x M>,
+                A<M>: ::core::clone::Clone,
+            {
+                type Output = A<M>;
+                fn add(self, rhs: A<M>) -> Self::Output {
+                    let mut lhs = ::core::clone::Clone::clone(self);
+                    let rhs = &rhs;
+                    <A<M> as AddAssign<&'_ A<M>>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl<'a, M> ::core::ops::Add<&'a A<M>> for A<M>
+            where
+                M: Sized + Zero + for<'x> AddAssign<&'x M>,
+            {
+                type Output = A<M>;
+                fn add(self, rhs: &'a A<M>) -> Self::Output {
+                    let mut lhs = self;
+                    <A<M> as AddAssign<&'_ A<M>>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl<M> ::core::ops::Add<A<M>> for A<M>
+            where
+                M: Sized + Zero + for<'x> AddAssign<&'x M>,
+            {
+                type Output = A<M>;
+                fn add(self, rhs: A<M>) -> Self::Output {
+                    let mut lhs = self;
+                    let rhs = &rhs;
+                    <A<M> as AddAssign<&'_ A<M>>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+        }
+    };
+}
+
+#[test]
+fn add_assign_select() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                assign_val,
+            },
+            quote! {
+                impl<'a, M> AddAssign<&'a A<M>> for A<M>
+                where
+                    M: Sized + Zero + for<'x> AddAssign<&'x M>,
+                {
+                    fn add_assign(&mut self, other: &Self) {
+                        self.0 += &other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl<M> ::core::ops::AddAssign<A<M>> for A<M>
+            where
+                M: Sized + Zero + for<'x> AddAssign<&'x M>,
+            {
+                fn add_assign(&mut self, rhs: A<M>) {
+                    let rhs = &rhs;
+                    <A<M> as AddAssign<&'_ A<M>>>::add_assign(self, rhs);
+                }
+            }
+            impl<'a, M> AddAssign<&'a A<M>> for A<M>
+            where
+                M: Sized + Zero + for<'x> AddAssign<&'x M>,
+            {
+                fn add_assign(&mut self, other: &Self) {
+                    self.0 += &other.0;
+                }
+            }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                ref_ref,
+            },
+            quote! {
+                impl<'a, M> AddAssign<&'a A<M>> for A<M>
+                where
+                    M: Sized + Zero + for<'x> AddAssign<&'x M>,
+                {
+                    fn add_assign(&mut self, other: &Self) {
+                        self.0 += &other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl<'a, M> ::core::ops::Add<&'a A<M>> for &A<M>
+            where
+                M: Sized + Zero + for<'x> AddAssign<&'x M>,
+                A<M>: ::core::clone::Clone,
+            {
+                type Output = A<M>;
+                fn add(self, rhs: &'a A<M>) -> Self::Output {
+                    let mut lhs = ::core::clone::Clone::clone(self);
+                    <A<M> as AddAssign<&'_ A<M>>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl<'a, M> AddAssign<&'a A<M>> for A<M>
+            where
+                M: Sized + Zero + for<'x> AddAssign<&'x M>,
+            {
+                fn add_assign(&mut self, other: &Self) {
+                    self.0 += &other.0;
+                }
+            }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                ref_val,
+            },
+            quote! {
+                impl<'a, M> AddAssign<&'a A<M>> for A<M>
+                where
+                    M: Sized + Zero + for<'x> AddAssign<&'x M>,
+                {
+                    fn add_assign(&mut self, other: &Self) {
+                        self.0 += &other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl<M> ::core::ops::Add<A<M>> for &A<M>
+            where
+                M: Sized + Zero + for<'x> AddAssign<&'x M>,
+                A<M>: ::core::clone::Clone,
+            {
+                type Output = A<M>;
+                fn add(self, rhs: A<M>) -> Self::Output {
+                    let mut lhs = ::core::clone::Clone::clone(self);
+                    let rhs = &rhs;
+                    <A<M> as AddAssign<&'_ A<M>>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl<'a, M> AddAssign<&'a A<M>> for A<M>
+            where
+                M: Sized + Zero + for<'x> AddAssign<&'x M>,
+            {
+                fn add_assign(&mut self, other: &Self) {
+                    self.0 += &other.0;
+                }
+            }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                val_ref,
+            },
+            quote! {
+                impl<'a, M> AddAssign<&'a A<M>> for A<M>
+                where
+                    M: Sized + Zero + for<'x> AddAssign<&'x M>,
+                {
+                    fn add_assign(&mut self, other: &Self) {
+                        self.0 += &other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl<'a, M> ::core::ops::Add<&'a A<M>> for A<M>
+            where
+                M: Sized + Zero + for<'x> AddAssign<&'x M>,
+            {
+                type Output = A<M>;
+                fn add(self, rhs: &'a A<M>) -> Self::Output {
+                    let mut lhs = self;
+                    <A<M> as AddAssign<&'_ A<M>>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl<'a, M> AddAssign<&'a A<M>> for A<M>
+            where
+                M: Sized + Zero + for<'x> AddAssign<&'x M>,
+            {
+                fn add_assign(&mut self, other: &Self) {
+                    self.0 += &other.0;
+                }
+            }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                val_val,
+            },
+            quote! {
+                impl<'a, M> AddAssign<&'a A<M>> for A<M>
+                where
+                    M: Sized + Zero + for<'x> AddAssign<&'x M>,
+                {
+                    fn add_assign(&mut self, other: &Self) {
+                        self.0 += &other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl<M> ::core::ops::Add<A<M>> for A<M>
+            where
+                M: Sized + Zero + for<'x> AddAssign<&'x M>,
+            {
+                type Output = A<M>;
+                fn add(self, rhs: A<M>) -> Self::Output {
+                    let mut lhs = self;
+                    let rhs = &rhs;
+                    <A<M> as AddAssign<&'_ A<M>>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl<'a, M> AddAssign<&'a A<M>> for A<M>
+            where
+                M: Sized + Zero + for<'x> AddAssign<&'x M>,
+            {
+                fn add_assign(&mut self, other: &Self) {
+                    self.0 += &other.0;
+                }
+            }
+        }
+    };
+}
+#[test]
+fn neg() {
+    assert_eq! {
+        auto_ops_impl(
+            TokenStream::new(),
+            quote! {
+                impl<M> Neg for A<M>
+                where
+                    M: Neg<Output = M>,
+                {
+                    type Output = Self;
+                    fn neg(self) -> Self::Output {
+                        A(-self.0)
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl<M> ::core::ops::Neg for &A<M>
+            where
+                M: Neg<Output = M>,
+                A<M>: ::core::clone::Clone,
+            {
+                type Output = A<M>;
+                fn neg(self) -> Self::Output {
+                    let lhs = ::core::clone::Clone::clone(self);
+                    <A<M> as Neg>::neg(lhs)
+                }
+            }
+            impl<M> Neg for A<M>
+            where
+                M: Neg<Output = M>,
+            {
+                type Output = A<M>;
+                fn neg(self) -> Self::Output {
+                    A(-self.0)
+                }
+            }
+        }
+    };
+}
+#[test]
+fn not_ref() {
+    assert_eq! {
+        auto_ops_impl(
+            TokenStream::new(),
+            quote! {
+                impl<'a> Not for &'a B {
+                    type Output = B;
+                    fn not(self) -> B {
+                        B(!self.0)
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl<'a> Not for &'a B {
+                type Output = B;
+                fn not(self) -> B {
+                    B(!self.0)
+                }
+            }
+            impl ::core::ops::Not for B {
+                type Output = B;
+                fn not(self) -> Self::Output {
+                    let lhs = &self;
+                    <&'_ B as Not>::not(lhs)
+                }
+            }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                ref,
+            },
+            quote! {
+                impl<'a> Not for &'a B {
+                    type Output = B;
+                    fn not(self) -> B {
+                        B(!self.0)
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl<'a> Not for &'a B {
+                type Output = B;
+                fn not(self) -> B {
+                    B(!self.0)
+                }
+            }
+        }
+    };
+}
+#[test]
+fn commutative() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                assign_ref, commutative(f64),
+            },
+            quote! {
+                impl<R> MulAssign<&R> for A<R>
+                where
+                    R: Sized + Zero + for<'x> MulAssign<&'x R>,
+                {
+                    fn mul_assign(&mut self, other: &R) {
+                        self.0 *= other;
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl<R> MulAssign<&R> for A<R>
+            where
+                R: Sized + Zero + for<'x> MulAssign<&'x R>,
+            {
+                fn mul_assign(&mut self, other: &R) {
+                    self.0 *= other;
+                }
+            }
+            impl ::core::ops::Mul<&A<f64>> for &f64
+            where
+                f64: Sized + Zero + for<'x> MulAssign<&'x f64>,
+                A<f64>: ::core::clone::Clone,
+            {
+                type Output = A<f64>;
+                fn mul(self, rhs: &A<f64>) -> Self::Output {
+                    <&A<f64> as ::core::ops::Mul<&f64>>::mul(rhs, self)
+                }
+            }
+            impl ::core::ops::Mul<A<f64>> for &f64
+            where
+                f64: Sized + Zero + for<'x> MulAssign<&'x f64>,
+            {
+                type Output = A<f64>;
+                fn mul(self, rhs: A<f64>) -> Self::Output {
+                    <A<f64> as ::core::ops::Mul<&f64>>::mul(rhs, self)
+                }
+            }
+            impl ::core::ops::Mul<&A<f64>> for f64
+            where
+                f64: Sized + Zero + for<'x> MulAssign<&'x f64>,
+                A<f64>: ::core::clone::Clone,
+            {
+                type Output = A<f64>;
+                fn mul(self, rhs: &A<f64>) -> Self::Output {
+                    <&A<f64> as ::core::ops::Mul<f64>>::mul(rhs, self)
+                }
+            }
+            impl ::core::ops::Mul<A<f64>> for f64
+            where
+                f64: Sized + Zero + for<'x> MulAssign<&'x f64>,
+            {
+                type Output = A<f64>;
+                fn mul(self, rhs: A<f64>) -> Self::Output {
+                    <A<f64> as ::core::ops::Mul<f64>>::mul(rhs, self)
+                }
+            }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                commutative,
+            },
+            quote! {
+                impl SubAssign<&f64> for B {
+                    fn sub_assign(&mut self, other: &f64) {
+                        self.0 -= other;
+                    }
+                }
+            },
+        ),
+        quote!{
+            compile_error!{ "`Sub` is not commutative (use `force_commutative` to generate mirrored impls anyway)" }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                commutative,
+            },
+            quote! {
+                impl<R> MulAssign<&R> for A<R> {
+                    fn mul_assign(&mut self, other: &R) {
+                        self.0 *= other;
+                    }
+                }
+            },
+        ),
+        quote!{
+            compile_error!{ "`commutative` cannot implement mirrored impls for the type parameter `R` because of the orphan rule (use `commutative(T1, T2, ...)` to list the types of `R`)" }
+        }
+    };
+}
+#[test]
+fn derive_tuple() {
+    assert_eq! {
+        derive::derive_auto_ops_impl(
+            quote! {
+                #[derive(Clone, AutoOps)]
+                #[ops(Add)]
+                struct P<T>(T, #[ops(skip)] u8, PhantomData<T>);
+            },
+            false,
+        ),
+        quote!{
+            impl<T> ::core::ops::AddAssign<&P<T>> for P<T>
+            where
+                T: for<'x> ::core::ops::AddAssign<&'x T>,
+            {
+                fn add_assign(&mut self, rhs: &Self) {
+                    ::core::ops::AddAssign::add_assign(&mut self.0, &rhs.0);
+                }
+            }
+            impl<T> ::core::ops::AddAssign<P<T>> for P<T>
+            where
+                T: for<'x> ::core::ops::AddAssign<&'x T>,
+            {
+                fn add_assign(&mut self, rhs: P<T>) {
+                    let rhs = &rhs;
+                    <P<T> as ::core::ops::AddAssign<&P<T>>>::add_assign(self, rhs);
+                }
+            }
+            impl<T> ::core::ops::Add<&P<T>> for &P<T>
+            where
+                T: for<'x> ::core::ops::AddAssign<&'x T>,
+                P<T>: ::core::clone::Clone,
+            {
+                type Output = P<T>;
+                fn add(self, rhs: &P<T>) -> Self::Output {
+                    let mut lhs = ::core::clone::Clone::clone(self);
+                    <P<T> as ::core::ops::AddAssign<&P<T>>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl<T> ::core::ops::Add<P<T>> for &P<T>
+            where
+                T: for<'x> ::core::ops::AddAssign<&'x T>,
+                P<T>: ::core::clone::Clone,
+            {
+                type Output = P<T>;
+                fn add(self, rhs: P<T>) -> Self::Output {
+                    let mut lhs = ::core::clone::Clone::clone(self);
+                    let rhs = &rhs;
+                    <P<T> as ::core::ops::AddAssign<&P<T>>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl<T> ::core::ops::Add<&P<T>> for P<T>
+            where
+                T: for<'x> ::core::ops::AddAssign<&'x T>,
+            {
+                type Output = P<T>;
+                fn add(self, rhs: &P<T>) -> Self::Output {
+                    let mut lhs = self;
+                    <P<T> as ::core::ops::AddAssign<&P<T>>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl<T> ::core::ops::Add<P<T>> for P<T>
+            where
+                T: for<'x> ::core::ops::AddAssign<&'x T>,
+            {
+                type Output = P<T>;
+                fn add(self, rhs: P<T>) -> Self::Output {
+                    let mut lhs = self;
+                    let rhs = &rhs;
+                    <P<T> as ::core::ops::AddAssign<&P<T>>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+        }
+    };
+}
+#[test]
+fn derive_named_skip() {
+    assert_eq! {
+        derive::derive_auto_ops_impl(
+            quote! {
+                #[derive(Clone, AutoOps)]
+                #[ops(Mul)]
+                struct Q {
+                    x: f64,
+                    #[ops(skip(Mul, Div))]
+                    y: f64,
+                }
+            },
+            false,
+        ),
+        quote!{
+            impl ::core::ops::MulAssign<&Q> for Q
+            where
+                f64: for<'x> ::core::ops::MulAssign<&'x f64>,
+            {
+                fn mul_assign(&mut self, rhs: &Self) {
+                    ::core::ops::MulAssign::mul_assign(&mut self.x, &rhs.x);
+                }
+            }
+            impl ::core::ops::MulAssign<Q> for Q
+            where
+                f64: for<'x> ::core::ops::MulAssign<&'x f64>,
+            {
+                fn mul_assign(&mut self, rhs: Q) {
+                    let rhs = &rhs;
+                    <Q as ::core::ops::MulAssign<&Q>>::mul_assign(self, rhs);
+                }
+            }
+            impl ::core::ops::Mul<&Q> for &Q
+            where
+                f64: for<'x> ::core::ops::MulAssign<&'x f64>,
+                Q: ::core::clone::Clone,
+            {
+                type Output = Q;
+                fn mul(self, rhs: &Q) -> Self::Output {
+                    let mut lhs = ::core::clone::Clone::clone(self);
+                    <Q as ::core::ops::MulAssign<&Q>>::mul_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl ::core::ops::Mul<Q> for &Q
+            where
+                f64: for<'x> ::core::ops::MulAssign<&'x f64>,
+                Q: ::core::clone::Clone,
+            {
+                type Output = Q;
+                fn mul(self, rhs: Q) -> Self::Output {
+                    let mut lhs = ::core::clone::Clone::clone(self);
+                    let rhs = &rhs;
+                    <Q as ::core::ops::MulAssign<&Q>>::mul_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl ::core::ops::Mul<&Q> for Q
+            where
+                f64: for<'x> ::core::ops::MulAssign<&'x f64>,
+            {
+                type Output = Q;
+                fn mul(self, rhs: &Q) -> Self::Output {
+                    let mut lhs = self;
+                    <Q as ::core::ops::MulAssign<&Q>>::mul_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl ::core::ops::Mul<Q> for Q
+            where
+                f64: for<'x> ::core::ops::MulAssign<&'x f64>,
+            {
+                type Output = Q;
+                fn mul(self, rhs: Q) -> Self::Output {
+                    let mut lhs = self;
+                    let rhs = &rhs;
+                    <Q as ::core::ops::MulAssign<&Q>>::mul_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+        }
+    };
+}
+#[test]
+fn derive_ops() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                assign_ref, derive_ops(Sub = Add + Neg),
+            },
+            quote! {
+                impl AddAssign<&B> for B {
+                    fn add_assign(&mut self, other: &Self) {
+                        self.0 += &other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl AddAssign<&B> for B {
+                fn add_assign(&mut self, other: &Self) {
+                    self.0 += &other.0;
+                }
+            }
+            impl ::core::ops::SubAssign<&B> for B
+            where
+                for<'x> &'x B: ::core::ops::Neg<Output = B>,
+            {
+                fn sub_assign(&mut self, rhs: &B) {
+                    <B as ::core::ops::AddAssign<
+                        &B,
+                    >>::add_assign(self, &<&B as ::core::ops::Neg>::neg(rhs));
+                }
+            }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                assign_ref, derive_ops(Add = Sub + Neg),
+            },
+            quote! {
+                impl AddAssign<&B> for B {
+                    fn add_assign(&mut self, other: &Self) {
+                        self.0 += &other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            compile_error!{ "`Add` is already implemented" }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                val_val, derive_ops(Sub = Add + Neg),
+            },
+            quote! {
+                impl Add<&B> for &B {
+                    type Output = B;
+                    fn add(self, other: &B) -> B {
+                        B(self.0 + other.0)
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl ::core::ops::Add<B> for B {
+                type Output = B;
+                fn add(self, rhs: B) -> Self::Output {
+                    let lhs = &self;
+                    let rhs = &rhs;
+                    <&B as Add<&B>>::add(lhs, rhs)
+                }
+            }
+            impl Add<&B> for &B {
+                type Output = B;
+                fn add(self, other: &B) -> B {
+                    B(self.0 + other.0)
+                }
+            }
+            impl ::core::ops::AddAssign<&B> for B {
+                fn add_assign(&mut self, rhs: &B) {
+                    *self = <&B as Add<&B>>::add(&*self, rhs);
+                }
+            }
+            impl ::core::ops::Sub<B> for B
+            where
+                for<'x> &'x B: ::core::ops::Neg<Output = B>,
+            {
+                type Output = B;
+                fn sub(self, rhs: B) -> Self::Output {
+                    let mut lhs = self;
+                    let rhs = &rhs;
+                    <B as ::core::ops::SubAssign<&B>>::sub_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl ::core::ops::SubAssign<&B> for B
+            where
+                for<'x> &'x B: ::core::ops::Neg<Output = B>,
+            {
+                fn sub_assign(&mut self, rhs: &B) {
+                    <B as ::core::ops::AddAssign<
+                        &B,
+                    >>::add_assign(self, &<&B as ::core::ops::Neg>::neg(rhs));
+                }
+            }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                derive_ops(Sub = Add + Neg),
+            },
+            quote! {
+                impl Add<&B> for &B {
+                    type Output = C;
+                    fn add(self, other: &B) -> C {
+                        C(self.0 + other.0)
+                    }
+                }
+            },
+        ),
+        quote!{
+            compile_error! {
+                "`derive_ops` requires that `Output` is `B`, but it is `C` (use `output_into` to convert it by `Into<B>`)"
+            }
+        }
+    };
+}
+#[test]
+fn broadcast() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                assign_ref, ref_ref, broadcast(M),
+            },
+            quote! {
+                impl<M> AddAssign<&A<M>> for A<M>
+                where
+                    M: for<'x> AddAssign<&'x M>,
+                {
+                    fn add_assign(&mut self, other: &Self) {
+                        self.0 += &other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl<M> AddAssign<&A<M>> for A<M>
+            where
+                M: for<'x> AddAssign<&'x M>,
+            {
+                fn add_assign(&mut self, other: &Self) {
+                    self.0 += &other.0;
+                }
+            }
+            impl<M> ::core::ops::Add<&A<M>> for &A<M>
+            where
+                M: for<'x> AddAssign<&'x M>,
+                A<M>: ::core::clone::Clone,
+            {
+                type Output = A<M>;
+                fn add(self, rhs: &A<M>) -> Self::Output {
+                    let mut lhs = ::core::clone::Clone::clone(self);
+                    <A<M> as AddAssign<&A<M>>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl<M> AddAssign<&M> for A<M>
+            where
+                M: for<'x> AddAssign<&'x M>,
+            {
+                fn add_assign(&mut self, other: &M) {
+                    self.0 += &(*other);
+                }
+            }
+            impl<M> ::core::ops::Add<&M> for &A<M>
+            where
+                M: for<'x> AddAssign<&'x M>,
+                A<M>: ::core::clone::Clone,
+            {
+                type Output = A<M>;
+                fn add(self, rhs: &M) -> Self::Output {
+                    let mut lhs = ::core::clone::Clone::clone(self);
+                    <A<M> as AddAssign<&M>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                broadcast(f64),
+            },
+            quote! {
+                impl AddAssign<B> for B {
+                    fn add_assign(&mut self, other: B) {
+                        self.0 += other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            compile_error!{ "`broadcast` requires that the type of RHS is a reference" }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                broadcast(f64),
+            },
+            quote! {
+                impl AddAssign<&O> for O {
+                    fn add_assign(&mut self, other: &Self) {
+                        self.p.x += other.p.x;
+                    }
+                }
+            },
+        ),
+        quote!{
+            compile_error!{ "`broadcast` requires that `other` is used only as `other.field`, `other.field[i]` or `xs.zip(other.field.iter())`, which are replaced with the scalar" }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                broadcast(f64),
+            },
+            quote! {
+                impl AddAssign<&V> for V {
+                    fn add_assign(&mut self, other: &Self) {
+                        for (x, y) in self.0.iter_mut().zip(other.0.iter()) { *x += y; }
+                        self.1[0] += other.1[0];
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl AddAssign<&V> for V {
+                fn add_assign(&mut self, other: &Self) {
+                    for (x, y) in self.0.iter_mut().zip(other.0.iter()) {
+                        *x += y;
+                    }
+                    self.1[0] += other.1[0];
+                }
+            }
+            impl ::core::ops::AddAssign<V> for V {
+                fn add_assign(&mut self, rhs: V) {
+                    let rhs = &rhs;
+                    <V as AddAssign<&V>>::add_assign(self, rhs);
+                }
+            }
+            impl ::core::ops::Add<&V> for &V
+            where
+                V: ::core::clone::Clone,
+            {
+                type Output = V;
+                fn add(self, rhs: &V) -> Self::Output {
+                    let mut lhs = ::core::clone::Clone::clone(self);
+                    <V as AddAssign<&V>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl ::core::ops::Add<V> for &V
+            where
+                V: ::core::clone::Clone,
+            {
+                type Output = V;
+                fn add(self, rhs: V) -> Self::Output {
+                    let mut lhs = ::core::clone::Clone::clone(self);
+                    let rhs = &rhs;
+                    <V as AddAssign<&V>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl ::core::ops::Add<&V> for V {
+                type Output = V;
+                fn add(self, rhs: &V) -> Self::Output {
+                    let mut lhs = self;
+                    <V as AddAssign<&V>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl ::core::ops::Add<V> for V {
+                type Output = V;
+                fn add(self, rhs: V) -> Self::Output {
+                    let mut lhs = self;
+                    let rhs = &rhs;
+                    <V as AddAssign<&V>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl AddAssign<&f64> for V {
+                fn add_assign(&mut self, other: &f64) {
+                    for (x, y) in self.0.iter_mut().zip(::core::iter::repeat(other)) {
+                        *x += y;
+                    }
+                    self.1[0] += (*other);
+                }
+            }
+            impl ::core::ops::AddAssign<f64> for V {
+                fn add_assign(&mut self, rhs: f64) {
+                    let rhs = &rhs;
+                    <V as AddAssign<&f64>>::add_assign(self, rhs);
+                }
+            }
+            impl ::core::ops::Add<&f64> for &V
+            where
+                V: ::core::clone::Clone,
+            {
+                type Output = V;
+                fn add(self, rhs: &f64) -> Self::Output {
+                    let mut lhs = ::core::clone::Clone::clone(self);
+                    <V as AddAssign<&f64>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl ::core::ops::Add<f64> for &V
+            where
+                V: ::core::clone::Clone,
+            {
+                type Output = V;
+                fn add(self, rhs: f64) -> Self::Output {
+                    let mut lhs = ::core::clone::Clone::clone(self);
+                    let rhs = &rhs;
+                    <V as AddAssign<&f64>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl ::core::ops::Add<&f64> for V {
+                type Output = V;
+                fn add(self, rhs: &f64) -> Self::Output {
+                    let mut lhs = self;
+                    <V as AddAssign<&f64>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl ::core::ops::Add<f64> for V {
+                type Output = V;
+                fn add(self, rhs: f64) -> Self::Output {
+                    let mut lhs = self;
+                    let rhs = &rhs;
+                    <V as AddAssign<&f64>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                broadcast(f64),
+            },
+            quote! {
+                impl AddAssign<&V> for V {
+                    fn add_assign(&mut self, other: &Self) {
+                        for i in 0..other.0.len() { self.0[i] += other.0[i]; }
+                    }
+                }
+            },
+        ),
+        quote!{
+            compile_error!{ "`broadcast` requires that `other` is used only as `other.field`, `other.field[i]` or `xs.zip(other.field.iter())`, which are replaced with the scalar" }
+        }
+    };
+}
+#[test]
+fn sum() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                assign_ref, sum,
+            },
+            quote! {
+                impl<M> AddAssign<&A<M>> for A<M>
+                where
+                    M: for<'x> AddAssign<&'x M>,
+                {
+                    fn add_assign(&mut self, other: &Self) {
+                        self.0 += &other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl<M> AddAssign<&A<M>> for A<M>
+            where
+                M: for<'x> AddAssign<&'x M>,
+            {
+                fn add_assign(&mut self, other: &Self) {
+                    self.0 += &other.0;
+                }
+            }
+            impl<M> ::core::ops::AddAssign<A<M>> for A<M>
+            where
+                M: for<'x> AddAssign<&'x M>,
+            {
+                fn add_assign(&mut self, rhs: A<M>) {
+                    let rhs = &rhs;
+                    <A<M> as AddAssign<&A<M>>>::add_assign(self, rhs);
+                }
+            }
+            impl<M> ::core::iter::Sum for A<M>
+            where
+                M: for<'x> AddAssign<&'x M>,
+                A<M>: ::core::default::Default,
+            {
+                fn sum<I: ::core::iter::Iterator<Item = Self>>(iter: I) -> Self {
+                    iter.fold(
+                        <Self as ::core::default::Default>::default(),
+                        |mut acc, x| {
+                            <A<M> as ::core::ops::AddAssign<Self>>::add_assign(&mut acc, x);
+                            acc
+                        },
+                    )
+                }
+            }
+            impl<'a, M> ::core::iter::Sum<&'a A<M>> for A<M>
+            where
+                M: for<'x> AddAssign<&'x M>,
+                A<M>: ::core::default::Default,
+            {
+                fn sum<I: ::core::iter::Iterator<Item = &'a Self>>(iter: I) -> Self {
+                    iter.fold(
+                        <Self as ::core::default::Default>::default(),
+                        |mut acc, x| {
+                            <A<M> as ::core::ops::AddAssign<&'a Self>>::add_assign(&mut acc, x);
+                            acc
+                        },
+                    )
+                }
+            }
+        }
+    };
+}
+#[test]
+fn product() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                val_val, product(B(1)),
+            },
+            quote! {
+                impl<'a> Mul<&'a B> for &'a B {
+                    type Output = B;
+                    fn mul(self, other: &B) -> B {
+                        B(self.0 * other.0)
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl ::core::ops::Mul<B> for B {
+                type Output = B;
+                fn mul(self, rhs: B) -> Self::Output {
+                    let lhs = &self;
+                    let rhs = &rhs;
+                    <&'_ B as Mul<&'_ B>>::mul(lhs, rhs)
+                }
+            }
+            impl<'a> Mul<&'a B> for &'a B {
+                type Output = B;
+                fn mul(self, other: &B) -> B {
+                    B(self.0 * other.0)
+                }
+            }
+            impl<'a> ::core::ops::MulAssign<&'a B> for B {
+                fn mul_assign(&mut self, rhs: &'a B) {
+                    *self = <&'_ B as Mul<&'_ B>>::mul(&*self, rhs);
+                }
+            }
+            impl ::core::ops::MulAssign<B> for B {
+                fn mul_assign(&mut self, rhs: B) {
+                    let rhs = &rhs;
+                    *self = <&'_ B as Mul<&'_ B>>::mul(&*self, rhs);
+                }
+            }
+            impl ::core::iter::Product for B {
+                fn product<I: ::core::iter::Iterator<Item = Self>>(iter: I) -> Self {
+                    iter.fold(
+                        B(1),
+                        |mut acc, x| {
+                            <B as ::core::ops::MulAssign<Self>>::mul_assign(&mut acc, x);
+                            acc
+                        },
+                    )
+                }
+            }
+            impl<'b> ::core::iter::Product<&'b B> for B {
+                fn product<I: ::core::iter::Iterator<Item = &'b Self>>(iter: I) -> Self {
+                    iter.fold(
+                        B(1),
+                        |mut acc, x| {
+                            <B as ::core::ops::MulAssign<&'b Self>>::mul_assign(&mut acc, x);
+                            acc
+                        },
+                    )
+                }
+            }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                ref_ref, sum,
+            },
+            quote! {
+                impl AddAssign<&B> for B {
+                    fn add_assign(&mut self, other: &B) {
+                        self.0 += other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl ::core::ops::Add<&B> for &B
+            where
+                B: ::core::clone::Clone,
+            {
+                type Output = B;
+                fn add(self, rhs: &B) -> Self::Output {
+                    let mut lhs = ::core::clone::Clone::clone(self);
+                    <B as AddAssign<&B>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl AddAssign<&B> for B {
+                fn add_assign(&mut self, other: &B) {
+                    self.0 += other.0;
+                }
+            }
+            impl ::core::ops::AddAssign<B> for B {
+                fn add_assign(&mut self, rhs: B) {
+                    let rhs = &rhs;
+                    <B as AddAssign<&B>>::add_assign(self, rhs);
+                }
+            }
+            impl ::core::iter::Sum for B
+            where
+                B: ::core::default::Default,
+            {
+                fn sum<I: ::core::iter::Iterator<Item = Self>>(iter: I) -> Self {
+                    iter.fold(
+                        <Self as ::core::default::Default>::default(),
+                        |mut acc, x| {
+                            <B as ::core::ops::AddAssign<Self>>::add_assign(&mut acc, x);
+                            acc
+                        },
+                    )
+                }
+            }
+            impl<'a> ::core::iter::Sum<&'a B> for B
+            where
+                B: ::core::default::Default,
+            {
+                fn sum<I: ::core::iter::Iterator<Item = &'a Self>>(iter: I) -> Self {
+                    iter.fold(
+                        <Self as ::core::default::Default>::default(),
+                        |mut acc, x| {
+                            <B as ::core::ops::AddAssign<&'a Self>>::add_assign(&mut acc, x);
+                            acc
+                        },
+                    )
+                }
+            }
+        }
+    };
+}
+#[test]
+#[cfg(feature = "num-traits")]
+fn checked() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                val_ref,
+            },
+            quote! {
+                impl<T> CheckedAdd for A<T>
+                where
+                    T: CheckedAdd,
+                {
+                    fn checked_add(&self, other: &Self) -> Option<Self> {
+                        self.0.checked_add(&other.0).map(A)
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl<T> CheckedAdd for A<T>
+            where
+                T: CheckedAdd,
+            {
+                fn checked_add(&self, other: &Self) -> Option<Self> {
+                    self.0.checked_add(&other.0).map(A)
+                }
+            }
+            impl<T> ::core::ops::Add<&A<T>> for A<T>
+            where
+                T: CheckedAdd,
+            {
+                type Output = A<T>;
+                fn add(self, rhs: &A<T>) -> Self::Output {
+                    let lhs = &self;
+                    <&A<T> as ::core::ops::Add<&A<T>>>::add(lhs, rhs)
+                }
+            }
+            impl<T> ::core::ops::Add<&A<T>> for &A<T>
+            where
+                T: CheckedAdd,
+            {
+                type Output = A<T>;
+                fn add(self, rhs: &A<T>) -> Self::Output {
+                    ::num_traits::CheckedAdd::checked_add(self, rhs)
+                        .expect("attempt to add with overflow")
+                }
+            }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            TokenStream::new(),
+            quote! {
+                impl WrappingAdd for B {
+                    fn wrapping_add(&self, other: &Self) -> Self {
+                        B(self.0.wrapping_add(other.0))
+                    }
+                }
+            },
+        ),
+        quote!{
+            compile_error!{ "There is nothing to generate from `WrappingAdd`, use `#[auto_ops]` on inherent `wrapping_add` instead" }
+        }
+    };
+}
+#[test]
+#[cfg(feature = "num-traits")]
+fn checked_inherent() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                ref_val,
+            },
+            quote! {
+                impl B {
+                    fn new(x: i32) -> Self {
+                        B(x)
+                    }
+                    fn checked_sub(&self, other: &Self) -> Option<Self> {
+                        self.0.checked_sub(other.0).map(B)
+                    }
+                    fn saturating_mul(&self, other: &Self) -> Self {
+                        B(self.0.saturating_mul(other.0))
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl B {
+                fn new(x: i32) -> Self {
+                    B(x)
+                }
+                fn checked_sub(&self, other: &Self) -> Option<Self> {
+                    self.0.checked_sub(other.0).map(B)
+                }
+                fn saturating_mul(&self, other: &Self) -> Self {
+                    B(self.0.saturating_mul(other.0))
+                }
+            }
+            impl ::num_traits::CheckedSub for B {
+                fn checked_sub(&self, v: &Self) -> ::core::option::Option<Self> {
+                    Self::checked_sub(self, v)
+                }
+            }
+            impl ::core::ops::Sub<B> for &B {
+                type Output = B;
+                fn sub(self, rhs: B) -> Self::Output {
+                    let lhs = self;
+                    let rhs = &rhs;
+                    <&B as ::core::ops::Sub<&B>>::sub(lhs, rhs)
+                }
+            }
+            impl ::core::ops::Sub<&B> for &B {
+                type Output = B;
+                fn sub(self, rhs: &B) -> Self::Output {
+                    ::num_traits::CheckedSub::checked_sub(self, rhs)
+                        .expect("attempt to subtract with overflow")
+                }
+            }
+            impl ::num_traits::SaturatingMul for B {
+                fn saturating_mul(&self, v: &Self) -> Self {
+                    Self::saturating_mul(self, v)
+                }
+            }
+        }
+    };
+}
+
+#[test]
+fn custom() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                custom(trait = Pow, method = pow),
+            },
+            quote! {
+                impl num_traits::Pow<&A> for &A {
+                    type Output = A;
+                    fn pow(self, other: &A) -> Self::Output {
+                        A(self.0.pow(other.0))
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl num_traits::Pow<&A> for &A {
+                type Output = A;
+                fn pow(self, other: &A) -> Self::Output {
+                    A(self.0.pow(other.0))
+                }
+            }
+            impl num_traits::Pow<A> for &A {
+                type Output = A;
+                fn pow(self, rhs: A) -> Self::Output {
+                    let lhs = self;
+                    let rhs = &rhs;
+                    <&A as num_traits::Pow<&A>>::pow(lhs, rhs)
+                }
+            }
+            impl num_traits::Pow<&A> for A {
+                type Output = A;
+                fn pow(self, rhs: &A) -> Self::Output {
+                    let lhs = &self;
+                    <&A as num_traits::Pow<&A>>::pow(lhs, rhs)
+                }
+            }
+            impl num_traits::Pow<A> for A {
+                type Output = A;
+                fn pow(self, rhs: A) -> Self::Output {
+                    let lhs = &self;
+                    let rhs = &rhs;
+                    <&A as num_traits::Pow<&A>>::pow(lhs, rhs)
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn custom_alias() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                assign_val, val_val,
+                custom(trait = Plus, method = add, assign = PlusAssign, assign_method = add_assign),
+            },
+            quote! {
+                impl PlusAssign<&A> for A {
+                    fn add_assign(&mut self, other: &Self) {
+                        self.0 += other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl PlusAssign<A> for A {
+                fn add_assign(&mut self, rhs: A) {
+                    let rhs = &rhs;
+                    <A as PlusAssign<&A>>::add_assign(self, rhs);
+                }
+            }
+            impl Plus<A> for A {
+                type Output = A;
+                fn add(self, rhs: A) -> Self::Output {
+                    let mut lhs = self;
+                    let rhs = &rhs;
+                    <A as PlusAssign<&A>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl PlusAssign<&A> for A {
+                fn add_assign(&mut self, other: &Self) {
+                    self.0 += other.0;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "num-traits")]
+#[test]
+fn mul_add() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                assign_val_ref, ref_ref_ref, val_ref_val,
+            },
+            quote! {
+                impl MulAddAssign<&A, &B> for A {
+                    fn mul_add_assign(&mut self, a: &A, b: &B) {
+                        self.0 = self.0 * a.0 + b.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl ::num_traits::MulAddAssign<A, &B> for A {
+                fn mul_add_assign(&mut self, a: A, b: &B) {
+                    let a = &a;
+                    <A as MulAddAssign<&A, &B>>::mul_add_assign(self, a, b);
+                }
+            }
+            impl ::num_traits::MulAdd<&A, &B> for &A
+            where
+                A: ::core::clone::Clone,
+            {
+                type Output = A;
+                fn mul_add(self, a: &A, b: &B) -> Self::Output {
+                    let mut lhs = ::core::clone::Clone::clone(self);
+                    <A as MulAddAssign<&A, &B>>::mul_add_assign(&mut lhs, a, b);
+                    lhs
+                }
+            }
+            impl ::num_traits::MulAdd<&A, B> for A {
+                type Output = A;
+                fn mul_add(self, a: &A, b: B) -> Self::Output {
+                    let mut lhs = self;
+                    let b = &b;
+                    <A as MulAddAssign<&A, &B>>::mul_add_assign(&mut lhs, a, b);
+                    lhs
+                }
+            }
+            impl MulAddAssign<&A, &B> for A {
+                fn mul_add_assign(&mut self, a: &A, b: &B) {
+                    self.0 = self.0 * a.0 + b.0;
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn partial_ord() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {},
+            quote! {
+                impl PartialOrd<B> for A {
+                    fn partial_cmp(&self, other: &B) -> Option<Ordering> {
+                        self.0.partial_cmp(&other.0)
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl PartialOrd<B> for A {
+                fn partial_cmp(&self, other: &B) -> Option<Ordering> {
+                    self.0.partial_cmp(&other.0)
+                }
+            }
+            impl ::core::cmp::PartialOrd<&B> for A {
+                fn partial_cmp(&self, other: &&B) -> ::core::option::Option<::core::cmp::Ordering> {
+                    ::core::cmp::PartialOrd::partial_cmp(self, *other)
+                }
+            }
+            impl ::core::cmp::PartialOrd<B> for &A {
+                fn partial_cmp(&self, other: &B) -> ::core::option::Option<::core::cmp::Ordering> {
+                    ::core::cmp::PartialOrd::partial_cmp(*self, other)
+                }
+            }
+            impl ::core::cmp::PartialOrd<A> for B {
+                fn partial_cmp(&self, other: &A) -> ::core::option::Option<::core::cmp::Ordering> {
+                    ::core::cmp::PartialOrd::partial_cmp(other, self)
+                        .map(::core::cmp::Ordering::reverse)
+                }
+            }
+            impl ::core::cmp::PartialOrd<&A> for B {
+                fn partial_cmp(&self, other: &&A) -> ::core::option::Option<::core::cmp::Ordering> {
+                    ::core::cmp::PartialOrd::partial_cmp(*other, self)
+                        .map(::core::cmp::Ordering::reverse)
+                }
+            }
+            impl ::core::cmp::PartialOrd<A> for &B {
+                fn partial_cmp(&self, other: &A) -> ::core::option::Option<::core::cmp::Ordering> {
+                    ::core::cmp::PartialOrd::partial_cmp(other, *self)
+                        .map(::core::cmp::Ordering::reverse)
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn lift() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                val_val, lift(ops),
+            },
+            quote! {
+                impl Add<&A> for &A {
+                    type Output = A;
+                    fn add(self, other: &A) -> Self::Output {
+                        A(self.0 + other.0)
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl ::core::ops::Add<A> for A {
+                type Output = A;
+                fn add(self, rhs: A) -> Self::Output {
+                    let lhs = &self;
+                    let rhs = &rhs;
+                    <&A as Add<&A>>::add(lhs, rhs)
+                }
+            }
+            impl Add<&A> for &A {
+                type Output = A;
+                fn add(self, other: &A) -> Self::Output {
+                    A(self.0 + other.0)
+                }
+            }
+            pub mod ops {
+                use super::*;
+                pub trait OptionAddExt<Rhs> {
+                    type Output;
+                    fn add_opt(
+                        self,
+                        rhs: ::core::option::Option<Rhs>,
+                    ) -> ::core::option::Option<Self::Output>;
+                }
+                impl<L: ::core::ops::Add<R>, R> OptionAddExt<R> for ::core::option::Option<L> {
+                    type Output = <L as ::core::ops::Add<R>>::Output;
+                    fn add_opt(
+                        self,
+                        rhs: ::core::option::Option<R>,
+                    ) -> ::core::option::Option<Self::Output> {
+                        match (self, rhs) {
+                            (
+                                ::core::option::Option::Some(lhs),
+                                ::core::option::Option::Some(rhs),
+                            ) => {
+                                ::core::option::Option::Some(
+                                    <L as ::core::ops::Add<R>>::add(lhs, rhs),
+                                )
+                            }
+                            _ => ::core::option::Option::None,
+                        }
+                    }
+                }
+                pub trait ResultAddExt<Rhs, E> {
+                    type Output;
+                    fn add_res(
+                        self,
+                        rhs: ::core::result::Result<Rhs, E>,
+                    ) -> ::core::result::Result<Self::Output, E>;
+                }
+                impl<L: ::core::ops::Add<R>, R, E> ResultAddExt<R, E>
+                for ::core::result::Result<L, E> {
+                    type Output = <L as ::core::ops::Add<R>>::Output;
+                    fn add_res(
+                        self,
+                        rhs: ::core::result::Result<R, E>,
+                    ) -> ::core::result::Result<Self::Output, E> {
+                        ::core::result::Result::Ok(<L as ::core::ops::Add<R>>::add(self?, rhs?))
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn lazy() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                ref_ref, lazy,
+            },
+            quote! {
+                impl AddAssign<&A> for A {
+                    fn add_assign(&mut self, other: &Self) {
+                        self.0 += &other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl<'a, 'b> ::core::ops::Add<&'b A> for &'a A {
+                type Output = AddExpr<&'a A, &'b A>;
+                fn add(self, rhs: &'b A) -> Self::Output {
+                    AddExpr(self, rhs)
+                }
+            }
+            impl AddAssign<&A> for A {
+                fn add_assign(&mut self, other: &Self) {
+                    self.0 += &other.0;
+                }
+            }
+            /// Unevaluated `Add` of `L` and `R`.
+            pub struct AddExpr<L, R>(pub L, pub R);
+            /// Evaluation of `AddExpr` into `T`.
+            pub trait AddLazy<T> {
+                /// Evaluates `self` into `T`.
+                fn eval(self) -> T;
+                /// Applies `AddAssign` with `self` to `target`.
+                fn apply(self, target: &mut T);
+            }
+            impl<T, L: AddLazy<T>, R: AddLazy<T>> AddLazy<T> for AddExpr<L, R> {
+                fn eval(self) -> T {
+                    let mut acc = AddLazy::eval(self.0);
+                    AddLazy::apply(self.1, &mut acc);
+                    acc
+                }
+                fn apply(self, target: &mut T) {
+                    AddLazy::apply(self.0, target);
+                    AddLazy::apply(self.1, target);
+                }
+            }
+            impl<L, R, X> ::core::ops::Add<X> for AddExpr<L, R> {
+                type Output = AddExpr<Self, X>;
+                fn add(self, rhs: X) -> Self::Output {
+                    AddExpr(self, rhs)
+                }
+            }
+            impl<L, R> ::core::ops::Add<AddExpr<L, R>> for A {
+                type Output = AddExpr<Self, AddExpr<L, R>>;
+                fn add(self, rhs: AddExpr<L, R>) -> Self::Output {
+                    AddExpr(self, rhs)
+                }
+            }
+            impl<'a, L, R> ::core::ops::Add<AddExpr<L, R>> for &'a A {
+                type Output = AddExpr<Self, AddExpr<L, R>>;
+                fn add(self, rhs: AddExpr<L, R>) -> Self::Output {
+                    AddExpr(self, rhs)
+                }
+            }
+            impl AddLazy<A> for A {
+                fn eval(self) -> A {
+                    self
+                }
+                fn apply(self, target: &mut A) {
+                    <A as ::core::ops::AddAssign<&A>>::add_assign(target, &self);
+                }
+            }
+            impl AddLazy<A> for &A
+            where
+                A: ::core::clone::Clone,
+            {
+                fn eval(self) -> A {
+                    ::core::clone::Clone::clone(self)
+                }
+                fn apply(self, target: &mut A) {
+                    <A as ::core::ops::AddAssign<&A>>::add_assign(target, self);
+                }
+            }
+            impl<L, R> ::core::ops::AddAssign<AddExpr<L, R>> for A
+            where
+                AddExpr<L, R>: AddLazy<A>,
+            {
+                fn add_assign(&mut self, rhs: AddExpr<L, R>) {
+                    AddLazy::apply(rhs, self);
+                }
+            }
+            impl<L, R> ::core::convert::From<AddExpr<L, R>> for A
+            where
+                AddExpr<L, R>: AddLazy<A>,
+            {
+                fn from(expr: AddExpr<L, R>) -> Self {
+                    AddLazy::eval(expr)
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn commutative_reuse_rhs() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                ref_val, commutative,
+            },
+            quote! {
+                impl AddAssign<&A> for A {
+                    fn add_assign(&mut self, other: &Self) {
+                        self.0 += &other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl ::core::ops::Add<A> for &A {
+                type Output = A;
+                fn add(self, rhs: A) -> Self::Output {
+                    let mut lhs = rhs;
+                    <A as AddAssign<&A>>::add_assign(&mut lhs, self);
+                    lhs
+                }
+            }
+            impl AddAssign<&A> for A {
+                fn add_assign(&mut self, other: &Self) {
+                    self.0 += &other.0;
+                }
+            }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                ref_val, commutative, force_commutative
+            },
+            quote! {
+                impl SubAssign<&S> for S {
+                    fn sub_assign(&mut self, other: &S) {
+                        self.0 -= other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl ::core::ops::Sub<S> for &S
+            where
+                S: ::core::clone::Clone,
+            {
+                type Output = S;
+                fn sub(self, rhs: S) -> Self::Output {
+                    let mut lhs = ::core::clone::Clone::clone(self);
+                    let rhs = &rhs;
+                    <S as SubAssign<&S>>::sub_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl SubAssign<&S> for S {
+                fn sub_assign(&mut self, other: &S) {
+                    self.0 -= other.0;
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn borrow_rhs() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                borrow_rhs,
+            },
+            quote! {
+                impl<M> AddAssign<&A<M>> for A<M>
+                where
+                    for<'x> M: AddAssign<&'x M>,
+                {
+                    fn add_assign(&mut self, other: &Self) {
+                        self.0 += &other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl<M> A<M>
+            where
+                for<'x> M: AddAssign<&'x M>,
+            {
+                #[doc(hidden)]
+                #[allow(dead_code, non_upper_case_globals)]
+                const borrow_rhs_Add: () = ();
+            }
+            impl<M, X: ::core::borrow::Borrow<A<M>>> ::core::ops::AddAssign<X> for A<M>
+            where
+                for<'x> M: AddAssign<&'x M>,
+            {
+                fn add_assign(&mut self, rhs: X) {
+                    let other: &A<M> = ::core::borrow::Borrow::borrow(&rhs);
+                    self.0 += &other.0;
+                }
+            }
+            impl<M, X: ::core::borrow::Borrow<A<M>>> ::core::ops::Add<X> for &A<M>
+            where
+                for<'x> M: AddAssign<&'x M>,
+                A<M>: ::core::clone::Clone,
+            {
+                type Output = A<M>;
+                fn add(self, rhs: X) -> Self::Output {
+                    let mut lhs = ::core::clone::Clone::clone(self);
+                    <A<M> as ::core::ops::AddAssign<X>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl<M, X: ::core::borrow::Borrow<A<M>>> ::core::ops::Add<X> for A<M>
+            where
+                for<'x> M: AddAssign<&'x M>,
+            {
+                type Output = A<M>;
+                fn add(self, rhs: X) -> Self::Output {
+                    let mut lhs = self;
+                    <A<M> as ::core::ops::AddAssign<X>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+        }
+    }
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                ref_ref, borrow_rhs, strict, allow_clone(ref_ref),
+            },
+            quote! {
+                impl SubAssign<&S> for S {
+                    fn sub_assign(&mut self, other: &S) {
+                        self.0 -= other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl S {
+                #[doc(hidden)]
+                #[allow(dead_code, non_upper_case_globals)]
+                const borrow_rhs_Sub: () = ();
+            }
+            impl<X: ::core::borrow::Borrow<S>> ::core::ops::SubAssign<X> for S {
+                fn sub_assign(&mut self, rhs: X) {
+                    let other: &S = ::core::borrow::Borrow::borrow(&rhs);
+                    self.0 -= other.0;
+                }
+            }
+            impl<X: ::core::borrow::Borrow<S>> ::core::ops::Sub<X> for &S
+            where
+                S: ::core::clone::Clone,
+            {
+                type Output = S;
+                fn sub(self, rhs: X) -> Self::Output {
+                    let mut lhs = ::core::clone::Clone::clone(self);
+                    <S as ::core::ops::SubAssign<X>>::sub_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                borrow_rhs, strict,
+            },
+            quote! {
+                impl SubAssign<&S> for S {
+                    fn sub_assign(&mut self, other: &S) {
+                        self.0 -= other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            compile_error! {
+                "`ref_ref` is not generated in `strict` mode because it clones LHS `S` (use `allow_clone(ref_ref)` to allow it)"
+            }
+        }
+    };
+}
+
+#[test]
+fn strict() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                strict,
+            },
+            quote! {
+                impl AddAssign<&A> for A {
+                    fn add_assign(&mut self, other: &Self) {
+                        self.0 += &other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            compile_error!{ "`ref_ref` is not generated in `strict` mode because it clones LHS `A` (use `allow_clone(ref_ref)` to allow it)" }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                assign_val, val_val, strict, derive_ops(Sub = Add + Neg),
+            },
+            quote! {
+                impl AddAssign<&A> for A {
+                    fn add_assign(&mut self, other: &Self) {
+                        self.0 += &other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl ::core::ops::AddAssign<A> for A {
+                fn add_assign(&mut self, rhs: A) {
+                    let rhs = &rhs;
+                    <A as AddAssign<&A>>::add_assign(self, rhs);
+                }
+            }
+            impl ::core::ops::Add<A> for A {
+                type Output = A;
+                fn add(self, rhs: A) -> Self::Output {
+                    let mut lhs = self;
+                    let rhs = &rhs;
+                    <A as AddAssign<&A>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl AddAssign<&A> for A {
+                fn add_assign(&mut self, other: &Self) {
+                    self.0 += &other.0;
+                }
+            }
+            impl ::core::ops::SubAssign<A> for A
+            where
+                for<'x> &'x A: ::core::ops::Neg<Output = A>,
+            {
+                fn sub_assign(&mut self, rhs: A) {
+                    let rhs = &rhs;
+                    <A as ::core::ops::SubAssign<&A>>::sub_assign(self, rhs);
+                }
+            }
+            impl ::core::ops::Sub<A> for A
+            where
+                for<'x> &'x A: ::core::ops::Neg<Output = A>,
+            {
+                type Output = A;
+                fn sub(self, rhs: A) -> Self::Output {
+                    let mut lhs = self;
+                    let rhs = &rhs;
+                    <A as ::core::ops::SubAssign<&A>>::sub_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl ::core::ops::SubAssign<&A> for A
+            where
+                for<'x> &'x A: ::core::ops::Neg<Output = A>,
+            {
+                fn sub_assign(&mut self, rhs: &A) {
+                    <A as ::core::ops::AddAssign<
+                        &A,
+                    >>::add_assign(self, &<&A as ::core::ops::Neg>::neg(rhs));
+                }
+            }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                val_val, ref_val, strict, lazy,
+            },
+            quote! {
+                impl AddAssign<&A> for A {
+                    fn add_assign(&mut self, other: &Self) {
+                        self.0 += &other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            compile_error!{ "`ref_val` is not generated in `strict` mode because it clones LHS `A` (use `allow_clone(ref_val)` to allow it)" }
+        }
+    };
+}
+
+#[test]
+fn strict_allow_clone() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                assign_val, ref_ref, val_ref, strict, allow_clone(ref_ref),
+            },
+            quote! {
+                impl AddAssign<&A> for A {
+                    fn add_assign(&mut self, other: &Self) {
+                        self.0 += &other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl ::core::ops::AddAssign<A> for A {
+                fn add_assign(&mut self, rhs: A) {
+                    let rhs = &rhs;
+                    <A as AddAssign<&A>>::add_assign(self, rhs);
+                }
+            }
+            impl ::core::ops::Add<&A> for &A
+            where
+                A: ::core::clone::Clone,
+            {
+                type Output = A;
+                fn add(self, rhs: &A) -> Self::Output {
+                    let mut lhs = ::core::clone::Clone::clone(self);
+                    <A as AddAssign<&A>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl ::core::ops::Add<&A> for A {
+                type Output = A;
+                fn add(self, rhs: &A) -> Self::Output {
+                    let mut lhs = self;
+                    <A as AddAssign<&A>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl AddAssign<&A> for A {
+                fn add_assign(&mut self, other: &Self) {
+                    self.0 += &other.0;
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn track_caller() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                assign_ref, val_val,
+            },
+            quote! {
+                impl<M> Div<&A<M>> for &A<M> {
+                    type Output = A<M>;
+                    #[track_caller]
+                    fn div(self, other: &A<M>) -> Self::Output {
+                        A(self.0.checked_div(other.0).expect("division by zero"))
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl<M> ::core::ops::DivAssign<&A<M>> for A<M> {
+                #[track_caller]
+                fn div_assign(&mut self, rhs: &A<M>) {
+                    *self = <&A<M> as Div<&A<M>>>::div(&*self, rhs);
+                }
+            }
+            impl<M> ::core::ops::Div<A<M>> for A<M> {
+                type Output = A<M>;
+                #[track_caller]
+                fn div(self, rhs: A<M>) -> Self::Output {
+                    let lhs = &self;
+                    let rhs = &rhs;
+                    <&A<M> as Div<&A<M>>>::div(lhs, rhs)
+                }
+            }
+            impl<M> Div<&A<M>> for &A<M> {
+                type Output = A<M>;
+                #[track_caller]
+                fn div(self, other: &A<M>) -> Self::Output {
+                    A(self.0.checked_div(other.0).expect("division by zero"))
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn track_caller_option() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                assign_val, ref_ref, track_caller,
+            },
+            quote! {
+                impl DivAssign<&A> for A {
+                    fn div_assign(&mut self, other: &Self) {
+                        assert_ne!(other.0, 0);
+                        self.0 /= other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl ::core::ops::DivAssign<A> for A {
+                #[track_caller]
+                fn div_assign(&mut self, rhs: A) {
+                    let rhs = &rhs;
+                    <A as DivAssign<&A>>::div_assign(self, rhs);
+                }
+            }
+            impl ::core::ops::Div<&A> for &A
+            where
+                A: ::core::clone::Clone,
+            {
+                type Output = A;
+                #[track_caller]
+                fn div(self, rhs: &A) -> Self::Output {
+                    let mut lhs = ::core::clone::Clone::clone(self);
+                    <A as DivAssign<&A>>::div_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl DivAssign<&A> for A {
+                #[track_caller]
+                fn div_assign(&mut self, other: &Self) {
+                    assert_ne!(other.0, 0);
+                    self.0 /= other.0;
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn resolve_self() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                ref_ref, val_val,
+            },
+            quote! {
+                impl<M> AddAssign<&Self> for A<M>
+                where
+                    Self: Debug,
+                {
+                    fn add_assign(&mut self, other: &Self) {
+                        self.0 += &other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl<M> ::core::ops::Add<&A<M>> for &A<M>
+            where
+                A<M>: Debug,
+                A<M>: ::core::clone::Clone,
+            {
+                type Output = A<M>;
+                fn add(self, rhs: &A<M>) -> Self::Output {
+                    let mut lhs = ::core::clone::Clone::clone(self);
+                    <A<M> as AddAssign<&A<M>>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl<M> ::core::ops::Add<A<M>> for A<M>
+            where
+                A<M>: Debug,
+            {
+                type Output = A<M>;
+                fn add(self, rhs: A<M>) -> Self::Output {
+                    let mut lhs = self;
+                    let rhs = &rhs;
+                    <A<M> as AddAssign<&A<M>>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl<M> AddAssign<&A<M>> for A<M>
+            where
+                A<M>: Debug,
+            {
+                fn add_assign(&mut self, other: &Self) {
+                    self.0 += &other.0;
+                }
+            }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                ref_ref,
+            },
+            quote! {
+                impl<M> Mul for A<M> {
+                    type Output = Wrapper<Self>;
+                    fn mul(self, other: Self) -> Self::Output {
+                        Wrapper(A(self.0 * other.0))
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl<M> ::core::ops::Mul<&A<M>> for &A<M>
+            where
+                A<M>: ::core::clone::Clone,
+            {
+                type Output = Wrapper<A<M>>;
+                fn mul(self, rhs: &A<M>) -> Self::Output {
+                    let lhs = ::core::clone::Clone::clone(self);
+                    let rhs = ::core::clone::Clone::clone(rhs);
+                    <A<M> as Mul>::mul(lhs, rhs)
+                }
+            }
+            impl<M> Mul for A<M> {
+                type Output = Wrapper<A<M>>;
+                fn mul(self, other: Self) -> Self::Output {
+                    Wrapper(A(self.0 * other.0))
+                }
+            }
+        }
+    };
+}
+
+/// Wraps tokens in a group without delimiters as `macro_rules!` does for `$t:ty`.
+macro_rules! grouped {
+    ($($t:tt)*) => {
+        proc_macro2::Group::new(proc_macro2::Delimiter::None, quote!($($t)*))
+    };
+}
+
+#[test]
+fn macro_rules_group() {
+    let t = grouped!(A<M>);
+    let ref_t = grouped!(&A<M>);
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                val_val, commutative,
+            },
+            quote! {
+                impl<M> Mul<#ref_t> for #ref_t {
+                    type Output = #t;
+                    fn mul(self, other: #ref_t) -> Self::Output {
+                        A(&self.0 * &other.0)
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl<M> ::core::ops::Mul<A<M>> for A<M> {
+                type Output = A<M>;
+                fn mul(self, rhs: A<M>) -> Self::Output {
+                    let lhs = &self;
+                    let rhs = &rhs;
+                    <&A<M> as Mul<&A<M>>>::mul(lhs, rhs)
+                }
+            }
+            impl<M> Mul<&A<M>> for &A<M> {
+                type Output = A<M>;
+                fn mul(self, other: &A<M>) -> Self::Output {
+                    A(&self.0 * &other.0)
+                }
+            }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                val_val,
+            },
+            quote! {
+                impl<M> AddAssign<&(#t)> for (A<M>) {
+                    fn add_assign(&mut self, other: &Self) {
+                        self.0 += &other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl<M> ::core::ops::Add<A<M>> for A<M> {
+                type Output = A<M>;
+                fn add(self, rhs: A<M>) -> Self::Output {
+                    let mut lhs = self;
+                    let rhs = &rhs;
+                    <A<M> as AddAssign<&A<M>>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl<M> AddAssign<&A<M>> for A<M> {
+                fn add_assign(&mut self, other: &Self) {
+                    self.0 += &other.0;
+                }
+            }
+        }
+    };
+}
+
+#[test]
+fn independent_lifetimes() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                ref_ref, val_val,
+            },
+            quote! {
+                impl<'a, 'b, M: 'b> AddAssign<&'a A<M>> for A<M> {
+                    fn add_assign(&mut self, other: &'a Self) {
+                        self.0 += &other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl<'a, 'b, M: 'b> ::core::ops::Add<&'a A<M>> for &A<M>
+            where
+                A<M>: ::core::clone::Clone,
+            {
+                type Output = A<M>;
+                fn add(self, rhs: &'a A<M>) -> Self::Output {
+                    let mut lhs = ::core::clone::Clone::clone(self);
+                    <A<M> as AddAssign<&'_ A<M>>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl<'b, M: 'b> ::core::ops::Add<A<M>> for A<M> {
+                type Output = A<M>;
+                fn add(self, rhs: A<M>) -> Self::Output {
+                    let mut lhs = self;
+                    let rhs = &rhs;
+                    <A<M> as AddAssign<&'_ A<M>>>::add_assign(&mut lhs, rhs);
+                    lhs
+                }
+            }
+            impl<'a, 'b, M: 'b> AddAssign<&'a A<M>> for A<M> {
+                fn add_assign(&mut self, other: &'a Self) {
+                    self.0 += &other.0;
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn take() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                assign_ref, take = clone
+            },
+            quote! {
+                impl Div<&A> for A {
+                    type Output = A;
+                    fn div(self, other: &A) -> A {
+                        A(self.0 / other.0)
+                    }
+                }
+            },
+        ),
+        quote!{
+            /// Moves out of `self` by cloning it.
+            /// If the operation panics, `self` is unchanged.
+            impl ::core::ops::DivAssign<&A> for A
+            where
+                A: ::core::clone::Clone,
+            {
+                fn div_assign(&mut self, rhs: &A) {
+                    *self = <A as Div<&A>>::div(::core::clone::Clone::clone(self), rhs);
+                }
+            }
+            impl Div<&A> for A {
+                type Output = A;
+                fn div(self, other: &A) -> A {
+                    A(self.0 / other.0)
+                }
+            }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                assign_ref, take = replace_with
+            },
+            quote! {
+                impl Div<&A> for A {
+                    type Output = A;
+                    fn div(self, other: &A) -> A {
+                        A(self.0 / other.0)
+                    }
+                }
+            },
+        ),
+        quote!{
+            /// Moves out of `self` like `replace_with::replace_with_or_abort`.
+            /// If the operation panics, the process is aborted.
+            impl ::core::ops::DivAssign<&A> for A {
+                fn div_assign(&mut self, rhs: &A) {
+                    ::auto_impl_ops::__private::replace_with_or_abort(
+                        self,
+                        |x| <A as Div<&A>>::div(x, rhs),
+                    );
+                }
+            }
+            impl Div<&A> for A {
+                type Output = A;
+                fn div(self, other: &A) -> A {
+                    A(self.0 / other.0)
+                }
+            }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                assign_ref, take = placeholder(A(0))
+            },
+            quote! {
+                impl Div<&A> for A {
+                    type Output = A;
+                    fn div(self, other: &A) -> A {
+                        A(self.0 / other.0)
+                    }
+                }
+            },
+        ),
+        quote!{
+            /// Moves out of `self` by replacing it with `A (0)`.
+            /// If the operation panics, `self` is left as `A (0)`.
+            impl ::core::ops::DivAssign<&A> for A {
+                fn div_assign(&mut self, rhs: &A) {
+                    let t = ::core::mem::replace(self, A(0));
+                    *self = <A as Div<&A>>::div(t, rhs);
+                }
+            }
+            impl Div<&A> for A {
+                type Output = A;
+                fn div(self, other: &A) -> A {
+                    A(self.0 / other.0)
+                }
+            }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                assign_ref, take = default, strict
+            },
+            quote! {
+                impl Div<&A> for A {
+                    type Output = A;
+                    fn div(self, other: &A) -> A {
+                        A(self.0 / other.0)
+                    }
+                }
+            },
+        ),
+        quote!{
+            compile_error! {
+                "`assign_ref` is not generated in `strict` mode because it moves out of `&mut self` by swapping with `A::default()` (use `allow_clone(assign_ref)` to allow it)"
+            }
+        }
+    }
+}
+
+#[test]
+fn crate_path() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                assign_ref, take = take_mut, crate = my_lib::ops
+            },
+            quote! {
+                impl Div<&A> for A {
+                    type Output = A;
+                    fn div(self, other: &A) -> A {
+                        A(self.0 / other.0)
+                    }
+                }
+            },
+        ),
+        quote!{
+            /// Moves out of `self` like `take_mut::take`.
+            /// If the operation panics, the process is aborted.
+            impl ::core::ops::DivAssign<&A> for A {
+                fn div_assign(&mut self, rhs: &A) {
+                    my_lib::ops::__private::take(self, |x| <A as Div<&A>>::div(x, rhs));
+                }
+            }
+            impl Div<&A> for A {
+                type Output = A;
+                fn div(self, other: &A) -> A {
+                    A(self.0 / other.0)
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn output_into() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {},
+            quote! {
+                impl Mul<&B> for &A {
+                    type Output = C;
+                    fn mul(self, other: &B) -> C {
+                        C(self.0 * other.0)
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl Mul<&B> for &A {
+                type Output = C;
+                fn mul(self, other: &B) -> C {
+                    C(self.0 * other.0)
+                }
+            }
+            impl ::core::ops::Mul<B> for &A {
+                type Output = C;
+                fn mul(self, rhs: B) -> Self::Output {
+                    let lhs = self;
+                    let rhs = &rhs;
+                    <&A as Mul<&B>>::mul(lhs, rhs)
+                }
+            }
+            impl ::core::ops::Mul<&B> for A {
+                type Output = C;
+                fn mul(self, rhs: &B) -> Self::Output {
+                    let lhs = &self;
+                    <&A as Mul<&B>>::mul(lhs, rhs)
+                }
+            }
+            impl ::core::ops::Mul<B> for A {
+                type Output = C;
+                fn mul(self, rhs: B) -> Self::Output {
+                    let lhs = &self;
+                    let rhs = &rhs;
+                    <&A as Mul<&B>>::mul(lhs, rhs)
+                }
+            }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                assign_ref
+            },
+            quote! {
+                impl Mul<&B> for &A {
+                    type Output = C;
+                    fn mul(self, other: &B) -> C {
+                        C(self.0 * other.0)
+                    }
+                }
+            },
+        ),
+        quote!{
+            compile_error! {
+                "`assign_ref` requires that `Output` is `A`, but it is `C` (use `output_into` to convert it by `Into<A>`)"
+            }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                assign_ref, assign_val, output_into
+            },
+            quote! {
+                impl Mul<&B> for &A {
+                    type Output = C;
+                    fn mul(self, other: &B) -> C {
+                        C(self.0 * other.0)
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl ::core::ops::MulAssign<&B> for A
+            where
+                C: ::core::convert::Into<A>,
+            {
+                fn mul_assign(&mut self, rhs: &B) {
+                    *self = ::core::convert::Into::into(<&A as Mul<&B>>::mul(&*self, rhs));
+                }
+            }
+            impl ::core::ops::MulAssign<B> for A
+            where
+                C: ::core::convert::Into<A>,
+            {
+                fn mul_assign(&mut self, rhs: B) {
+                    let rhs = &rhs;
+                    *self = ::core::convert::Into::into(<&A as Mul<&B>>::mul(&*self, rhs));
+                }
+            }
+            impl Mul<&B> for &A {
+                type Output = C;
+                fn mul(self, other: &B) -> C {
+                    C(self.0 * other.0)
+                }
+            }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                assign_ref, output_into, take = clone
+            },
+            quote! {
+                impl Mul<&B> for A {
+                    type Output = C;
+                    fn mul(self, other: &B) -> C {
+                        C(self.0 * other.0)
+                    }
+                }
+            },
+        ),
+        quote!{
+            /// Moves out of `self` by cloning it.
+            /// If the operation panics, `self` is unchanged.
+            impl ::core::ops::MulAssign<&B> for A
+            where
+                A: ::core::clone::Clone,
+                C: ::core::convert::Into<A>,
+            {
+                fn mul_assign(&mut self, rhs: &B) {
+                    *self = ::core::convert::Into::into(
+                        <A as Mul<&B>>::mul(::core::clone::Clone::clone(self), rhs),
+                    );
+                }
+            }
+            impl Mul<&B> for A {
+                type Output = C;
+                fn mul(self, other: &B) -> C {
+                    C(self.0 * other.0)
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn fresh_lifetime_hrtb() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                assign_ref, sum
+            },
+            quote! {
+                impl<M: Default> AddAssign<&A<M>> for A<M>
+                where
+                    for<'a> M: AddAssign<&'a M>,
+                {
+                    fn add_assign(&mut self, other: &Self) {
+                        self.0 += &other.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl<M: Default> AddAssign<&A<M>> for A<M>
+            where
+                for<'a> M: AddAssign<&'a M>,
+            {
+                fn add_assign(&mut self, other: &Self) {
+                    self.0 += &other.0;
+                }
+            }
+            impl<M: Default> ::core::ops::AddAssign<A<M>> for A<M>
+            where
+                for<'a> M: AddAssign<&'a M>,
+            {
+                fn add_assign(&mut self, rhs: A<M>) {
+                    let rhs = &rhs;
+                    <A<M> as AddAssign<&A<M>>>::add_assign(self, rhs);
+                }
+            }
+            impl<M: Default> ::core::iter::Sum for A<M>
+            where
+                for<'a> M: AddAssign<&'a M>,
+                A<M>: ::core::default::Default,
+            {
+                fn sum<I: ::core::iter::Iterator<Item = Self>>(iter: I) -> Self {
+                    iter.fold(
+                        <Self as ::core::default::Default>::default(),
+                        |mut acc, x| {
+                            <A<M> as ::core::ops::AddAssign<Self>>::add_assign(&mut acc, x);
+                            acc
+                        },
+                    )
+                }
+            }
+            impl<'b, M: Default> ::core::iter::Sum<&'b A<M>> for A<M>
+            where
+                for<'a> M: AddAssign<&'a M>,
+                A<M>: ::core::default::Default,
+            {
+                fn sum<I: ::core::iter::Iterator<Item = &'b Self>>(iter: I) -> Self {
+                    iter.fold(
+                        <Self as ::core::default::Default>::default(),
+                        |mut acc, x| {
+                            <A<M> as ::core::ops::AddAssign<&'b Self>>::add_assign(&mut acc, x);
+                            acc
+                        },
+                    )
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn track_caller_unary() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {},
+            quote! {
+                impl Neg for A {
+                    type Output = A;
+                    #[track_caller]
+                    fn neg(self) -> A {
+                        A(self.0.checked_neg().unwrap())
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl ::core::ops::Neg for &A
+            where
+                A: ::core::clone::Clone,
+            {
+                type Output = A;
+                #[track_caller]
+                fn neg(self) -> Self::Output {
+                    let lhs = ::core::clone::Clone::clone(self);
+                    <A as Neg>::neg(lhs)
+                }
+            }
+            impl Neg for A {
+                type Output = A;
+                #[track_caller]
+                fn neg(self) -> A {
+                    A(self.0.checked_neg().unwrap())
+                }
+            }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                track_caller
+            },
+            quote! {
+                impl Not for &B {
+                    type Output = B;
+                    fn not(self) -> B {
+                        B(!self.0)
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl Not for &B {
+                type Output = B;
+                #[track_caller]
+                fn not(self) -> B {
+                    B(!self.0)
+                }
+            }
+            impl ::core::ops::Not for B {
+                type Output = B;
+                #[track_caller]
+                fn not(self) -> Self::Output {
+                    let lhs = &self;
+                    <&B as Not>::not(lhs)
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn track_caller_derive_ops() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                assign_val, track_caller, derive_ops(Sub = Add + Neg)
+            },
+            quote! {
+                impl AddAssign<&A> for A {
+                    fn add_assign(&mut self, other: &Self) {
+                        self.0 = self.0.checked_add(other.0).unwrap();
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl ::core::ops::AddAssign<A> for A {
+                #[track_caller]
+                fn add_assign(&mut self, rhs: A) {
+                    let rhs = &rhs;
+                    <A as AddAssign<&A>>::add_assign(self, rhs);
+                }
+            }
+            impl AddAssign<&A> for A {
+                #[track_caller]
+                fn add_assign(&mut self, other: &Self) {
+                    self.0 = self.0.checked_add(other.0).unwrap();
+                }
+            }
+            impl ::core::ops::SubAssign<A> for A
+            where
+                for<'x> &'x A: ::core::ops::Neg<Output = A>,
+            {
+                #[track_caller]
+                fn sub_assign(&mut self, rhs: A) {
+                    let rhs = &rhs;
+                    <A as ::core::ops::SubAssign<&A>>::sub_assign(self, rhs);
+                }
+            }
+            impl ::core::ops::SubAssign<&A> for A
+            where
+                for<'x> &'x A: ::core::ops::Neg<Output = A>,
+            {
+                #[track_caller]
+                fn sub_assign(&mut self, rhs: &A) {
+                    <A as ::core::ops::AddAssign<
+                        &A,
+                    >>::add_assign(self, &<&A as ::core::ops::Neg>::neg(rhs));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "num-traits")]
+fn track_caller_mul_add() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                assign_ref_ref,
+            },
+            quote! {
+                impl MulAdd<&A, &A> for &A {
+                    type Output = A;
+                    #[track_caller]
+                    fn mul_add(self, a: &A, b: &A) -> A {
+                        A(self.0.checked_mul(a.0).unwrap() + b.0)
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl ::num_traits::MulAddAssign<&A, &A> for A {
+                #[track_caller]
+                fn mul_add_assign(&mut self, a: &A, b: &A) {
+                    *self = <&A as MulAdd<&A, &A>>::mul_add(&*self, a, b);
+                }
+            }
+            impl MulAdd<&A, &A> for &A {
+                type Output = A;
+                #[track_caller]
+                fn mul_add(self, a: &A, b: &A) -> A {
+                    A(self.0.checked_mul(a.0).unwrap() + b.0)
+                }
+            }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                val_val_val, track_caller,
+            },
+            quote! {
+                impl MulAddAssign<&A, &A> for A {
+                    fn mul_add_assign(&mut self, a: &A, b: &A) {
+                        self.0 = self.0.checked_mul(a.0).unwrap() + b.0;
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl ::num_traits::MulAdd<A, A> for A {
+                type Output = A;
+                #[track_caller]
+                fn mul_add(self, a: A, b: A) -> Self::Output {
+                    let mut lhs = self;
+                    let a = &a;
+                    let b = &b;
+                    <A as MulAddAssign<&A, &A>>::mul_add_assign(&mut lhs, a, b);
+                    lhs
+                }
+            }
+            impl MulAddAssign<&A, &A> for A {
+                #[track_caller]
+                fn mul_add_assign(&mut self, a: &A, b: &A) {
+                    self.0 = self.0.checked_mul(a.0).unwrap() + b.0;
+                }
+            }
+        }
+    };
+}
+
+#[test]
+#[cfg(feature = "num-traits")]
+fn track_caller_checked() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                ref_val, track_caller,
+            },
+            quote! {
+                impl B {
+                    fn new(x: i32) -> Self {
+                        B(x)
+                    }
+                    fn checked_sub(&self, other: &Self) -> Option<Self> {
+                        self.0.checked_sub(other.0).map(B)
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl B {
+                fn new(x: i32) -> Self {
+                    B(x)
+                }
+                #[track_caller]
+                fn checked_sub(&self, other: &Self) -> Option<Self> {
+                    self.0.checked_sub(other.0).map(B)
+                }
+            }
+            impl ::num_traits::CheckedSub for B {
+                #[track_caller]
+                fn checked_sub(&self, v: &Self) -> ::core::option::Option<Self> {
+                    Self::checked_sub(self, v)
+                }
+            }
+            impl ::core::ops::Sub<B> for &B {
+                type Output = B;
+                #[track_caller]
+                fn sub(self, rhs: B) -> Self::Output {
+                    let lhs = self;
+                    let rhs = &rhs;
+                    <&B as ::core::ops::Sub<&B>>::sub(lhs, rhs)
+                }
+            }
+            impl ::core::ops::Sub<&B> for &B {
+                type Output = B;
+                #[track_caller]
+                fn sub(self, rhs: &B) -> Self::Output {
+                    ::num_traits::CheckedSub::checked_sub(self, rhs)
+                        .expect("attempt to subtract with overflow")
+                }
+            }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                ref_val,
+            },
+            quote! {
+                impl CheckedAdd for A {
+                    #[track_caller]
+                    fn checked_add(&self, other: &Self) -> Option<Self> {
+                        self.0.checked_add(other.0).map(A)
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl CheckedAdd for A {
+                #[track_caller]
+                fn checked_add(&self, other: &Self) -> Option<Self> {
+                    self.0.checked_add(other.0).map(A)
+                }
+            }
+            impl ::core::ops::Add<A> for &A {
+                type Output = A;
+                #[track_caller]
+                fn add(self, rhs: A) -> Self::Output {
+                    let lhs = self;
+                    let rhs = &rhs;
+                    <&A as ::core::ops::Add<&A>>::add(lhs, rhs)
+                }
+            }
+            impl ::core::ops::Add<&A> for &A {
+                type Output = A;
+                #[track_caller]
+                fn add(self, rhs: &A) -> Self::Output {
+                    ::num_traits::CheckedAdd::checked_add(self, rhs)
+                        .expect("attempt to add with overflow")
+                }
+            }
+        }
+    };
+}
+
+#[test]
+#[cfg(feature = "num-traits")]
+fn mul_add_output_into() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {},
+            quote! {
+                impl MulAdd<&A, &A> for &A {
+                    type Output = C;
+                    fn mul_add(self, a: &A, b: &A) -> C {
+                        C(self.0 * a.0 + b.0)
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl MulAdd<&A, &A> for &A {
+                type Output = C;
+                fn mul_add(self, a: &A, b: &A) -> C {
+                    C(self.0 * a.0 + b.0)
+                }
+            }
+            impl ::num_traits::MulAdd<&A, A> for &A {
+                type Output = C;
+                fn mul_add(self, a: &A, b: A) -> Self::Output {
+                    let lhs = self;
+                    let b = &b;
+                    <&A as MulAdd<&A, &A>>::mul_add(lhs, a, b)
+                }
+            }
+            impl ::num_traits::MulAdd<A, &A> for &A {
+                type Output = C;
+                fn mul_add(self, a: A, b: &A) -> Self::Output {
+                    let lhs = self;
+                    let a = &a;
+                    <&A as MulAdd<&A, &A>>::mul_add(lhs, a, b)
+                }
+            }
+            impl ::num_traits::MulAdd<A, A> for &A {
+                type Output = C;
+                fn mul_add(self, a: A, b: A) -> Self::Output {
+                    let lhs = self;
+                    let a = &a;
+                    let b = &b;
+                    <&A as MulAdd<&A, &A>>::mul_add(lhs, a, b)
+                }
+            }
+            impl ::num_traits::MulAdd<&A, &A> for A {
+                type Output = C;
+                fn mul_add(self, a: &A, b: &A) -> Self::Output {
+                    let lhs = &self;
+                    <&A as MulAdd<&A, &A>>::mul_add(lhs, a, b)
+                }
+            }
+            impl ::num_traits::MulAdd<&A, A> for A {
+                type Output = C;
+                fn mul_add(self, a: &A, b: A) -> Self::Output {
+                    let lhs = &self;
+                    let b = &b;
+                    <&A as MulAdd<&A, &A>>::mul_add(lhs, a, b)
+                }
+            }
+            impl ::num_traits::MulAdd<A, &A> for A {
+                type Output = C;
+                fn mul_add(self, a: A, b: &A) -> Self::Output {
+                    let lhs = &self;
+                    let a = &a;
+                    <&A as MulAdd<&A, &A>>::mul_add(lhs, a, b)
+                }
+            }
+            impl ::num_traits::MulAdd<A, A> for A {
+                type Output = C;
+                fn mul_add(self, a: A, b: A) -> Self::Output {
+                    let lhs = &self;
+                    let a = &a;
+                    let b = &b;
+                    <&A as MulAdd<&A, &A>>::mul_add(lhs, a, b)
+                }
+            }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                assign_ref_ref
+            },
+            quote! {
+                impl MulAdd<&A, &A> for &A {
+                    type Output = C;
+                    fn mul_add(self, a: &A, b: &A) -> C {
+                        C(self.0 * a.0 + b.0)
+                    }
+                }
+            },
+        ),
+        quote!{
+            compile_error! {
+                "`assign_ref_ref` requires that `Output` is `A`, but it is `C` (use `output_into` to convert it by `Into<A>`)"
+            }
+        }
+    };
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                assign_ref_ref, output_into
+            },
+            quote! {
+                impl MulAdd<&A, &A> for &A {
+                    type Output = C;
+                    fn mul_add(self, a: &A, b: &A) -> C {
+                        C(self.0 * a.0 + b.0)
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl ::num_traits::MulAddAssign<&A, &A> for A
+            where
+                C: ::core::convert::Into<A>,
+            {
+                fn mul_add_assign(&mut self, a: &A, b: &A) {
+                    *self = ::core::convert::Into::into(
+                        <&A as MulAdd<&A, &A>>::mul_add(&*self, a, b),
+                    );
+                }
+            }
+            impl MulAdd<&A, &A> for &A {
+                type Output = C;
+                fn mul_add(self, a: &A, b: &A) -> C {
+                    C(self.0 * a.0 + b.0)
+                }
+            }
+        }
+    }
+}
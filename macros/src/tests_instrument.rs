@@ -0,0 +1,150 @@
+use super::*;
+fn to_pretty_string(tokens: proc_macro2::TokenStream) -> String {
+    prettyplease::unparse(&syn::parse2(tokens).unwrap())
+}
+/// Expands with counters regardless of `instrument` feature.
+fn auto_ops_impl(attrs: TokenStream, tokens: TokenStream) -> TokenStream {
+    super::auto_ops_impl(attrs, tokens, true)
+}
+macro_rules! assert_eq {
+    ($expected:expr, $result:expr) => {
+        pretty_assertions::assert_eq!(to_pretty_string($expected), to_pretty_string($result));
+    };
+}
+
+#[test]
+#[cfg(not(feature = "take_mut"))]
+fn instrument() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                assign_val, ref_ref, val_ref,
+            },
+            quote! {
+                impl Add<&A> for A {
+                    type Output = A;
+                    fn add(self, other: &Self) -> Self::Output {
+                        A(self.0 + other.0)
+                    }
+                }
+            },
+        ),
+        quote!{
+            /// Moves out of `self` by swapping it with `Self::default()`.
+            /// If the operation panics, `self` is left as `Self::default()`.
+            impl ::core::ops::AddAssign<A> for A
+            where
+                A: ::core::default::Default,
+            {
+                fn add_assign(&mut self, rhs: A) {
+                    static COUNTER: ::auto_impl_ops::__private::Counter = ::auto_impl_ops::__private::Counter::new(
+                        "assign_val",
+                    );
+                    COUNTER.record(::core::any::type_name::<A>(), 0u64, 1u64);
+                    let rhs = &rhs;
+                    let mut t = <Self as ::core::default::Default>::default();
+                    ::core::mem::swap(&mut t, self);
+                    let mut u = <A as Add<&A>>::add(t, rhs);
+                    ::core::mem::swap(&mut u, self);
+                }
+            }
+            impl ::core::ops::Add<&A> for &A
+            where
+                A: ::core::clone::Clone,
+            {
+                type Output = A;
+                fn add(self, rhs: &A) -> Self::Output {
+                    static COUNTER: ::auto_impl_ops::__private::Counter = ::auto_impl_ops::__private::Counter::new(
+                        "ref_ref",
+                    );
+                    COUNTER.record(::core::any::type_name::<A>(), 1u64, 0u64);
+                    let lhs = ::core::clone::Clone::clone(self);
+                    <A as Add<&A>>::add(lhs, rhs)
+                }
+            }
+            impl Add<&A> for A {
+                type Output = A;
+                fn add(self, other: &Self) -> Self::Output {
+                    A(self.0 + other.0)
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn instrument_commutative() {
+    assert_eq! {
+        auto_ops_impl(
+            quote! {
+                val_val, commutative,
+            },
+            quote! {
+                impl Mul<B> for A {
+                    type Output = A;
+                    fn mul(self, other: B) -> Self::Output {
+                        A(self.0 * other.0)
+                    }
+                }
+            },
+        ),
+        quote!{
+            impl Mul<B> for A {
+                type Output = A;
+                fn mul(self, other: B) -> Self::Output {
+                    A(self.0 * other.0)
+                }
+            }
+            impl ::core::ops::Mul<&A> for &B
+            where
+                A: ::core::clone::Clone,
+                B: ::core::clone::Clone,
+            {
+                type Output = A;
+                fn mul(self, rhs: &A) -> Self::Output {
+                    static COUNTER: ::auto_impl_ops::__private::Counter = ::auto_impl_ops::__private::Counter::new(
+                        "rev_ref_ref",
+                    );
+                    COUNTER.record(::core::any::type_name::<B>(), 0u64, 0u64);
+                    <&A as ::core::ops::Mul<&B>>::mul(rhs, self)
+                }
+            }
+            impl ::core::ops::Mul<A> for &B
+            where
+                B: ::core::clone::Clone,
+            {
+                type Output = A;
+                fn mul(self, rhs: A) -> Self::Output {
+                    static COUNTER: ::auto_impl_ops::__private::Counter = ::auto_impl_ops::__private::Counter::new(
+                        "rev_ref_val",
+                    );
+                    COUNTER.record(::core::any::type_name::<B>(), 0u64, 0u64);
+                    <A as ::core::ops::Mul<&B>>::mul(rhs, self)
+                }
+            }
+            impl ::core::ops::Mul<&A> for B
+            where
+                A: ::core::clone::Clone,
+            {
+                type Output = A;
+                fn mul(self, rhs: &A) -> Self::Output {
+                    static COUNTER: ::auto_impl_ops::__private::Counter = ::auto_impl_ops::__private::Counter::new(
+                        "rev_val_ref",
+                    );
+                    COUNTER.record(::core::any::type_name::<B>(), 0u64, 0u64);
+                    <&A as ::core::ops::Mul<B>>::mul(rhs, self)
+                }
+            }
+            impl ::core::ops::Mul<A> for B {
+                type Output = A;
+                fn mul(self, rhs: A) -> Self::Output {
+                    static COUNTER: ::auto_impl_ops::__private::Counter = ::auto_impl_ops::__private::Counter::new(
+                        "rev_val_val",
+                    );
+                    COUNTER.record(::core::any::type_name::<B>(), 0u64, 0u64);
+                    <A as ::core::ops::Mul<B>>::mul(rhs, self)
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use super::*;
+
+#[derive(Clone, Copy, Debug, Display, EnumString, PartialEq, Eq, Hash)]
+pub(crate) enum UnaryOpTrait {
+    Neg,
+    Not,
+    Inv,
+}
+impl ToTokens for UnaryOpTrait {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ident = Ident::new(&self.to_string(), Span::call_site());
+        match self {
+            Self::Inv => tokens.extend(quote!(::num_traits::#ident)),
+            _ => tokens.extend(quote!(::core::ops::#ident)),
+        }
+    }
+}
+impl UnaryOpTrait {
+    pub(crate) fn to_func_ident(self) -> Ident {
+        use UnaryOpTrait::*;
+        match self {
+            Neg => format_ident!("neg"),
+            Not => format_ident!("not"),
+            Inv => format_ident!("inv"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+struct UnaryOperate(UnaryOpTrait, bool);
+
+#[derive(Clone, Debug)]
+struct UnaryGenerator<'a> {
+    implement: &'a ItemImpl,
+    source_op: UnaryOperate,
+    self_type: &'a Type,
+    /// whether generated methods have `#[track_caller]`
+    track_caller: bool,
+    private: &'a Path,
+    /// whether generated methods count calls
+    instrument: bool,
+}
+impl UnaryGenerator<'_> {
+    fn gen_lhs(source_op: UnaryOperate, op: UnaryOperate) -> TokenStream {
+        let lhs = local("lhs");
+        if source_op.1 {
+            quote!(let #lhs = &self;)
+        } else {
+            debug_assert!(op.1);
+            quote!(let #lhs = ::core::clone::Clone::clone(self);)
+        }
+    }
+    fn generate(&self, op: UnaryOperate) -> Result<TokenStream> {
+        if op == self.source_op {
+            return Ok(self.implement.to_token_stream());
+        }
+        let mut work = self.implement.clone();
+        let trait_ = op.0;
+        work.trait_.as_mut().unwrap().1 = parse_quote! { #trait_ };
+        *work.self_ty.as_mut() = Generator::get_arg_type(op.1, self.self_type);
+        if !self.source_op.1 {
+            let rr_self_type = remove_reference(self.self_type);
+            let wc = work.generics.make_where_clause();
+            wc.predicates.push(parse_quote! {
+                #rr_self_type: ::core::clone::Clone
+            });
+        }
+        work.items.clear();
+        let output_type = get_output_type(self.implement, &format_ident!("Output"))?;
+        work.items.push(parse_quote! {
+            type Output = #output_type;
+        });
+        let fn_name = op.0.to_func_ident();
+        let variant = if op.1 { "ref" } else { "val" };
+        let clones = !self.source_op.1 as u64;
+        let instrument = gen_instrument(
+            self.instrument,
+            self.private,
+            self.self_type,
+            variant,
+            clones,
+            0,
+        );
+        let preamble_lhs = Self::gen_lhs(self.source_op, op);
+        let source_method = qualified_method(self.implement, &fn_name);
+        let lhs = local("lhs");
+        let track_caller = if self.track_caller {
+            quote!(#[track_caller])
+        } else {
+            TokenStream::new()
+        };
+        work.items.push(parse_quote! {
+            #track_caller
+            fn #fn_name(self) -> Self::Output {
+                #instrument
+                #preamble_lhs
+                #source_method(#lhs)
+            }
+        });
+        drop_unused_lifetimes(&mut work);
+        Ok(quote!(#work))
+    }
+}
+
+pub(crate) fn auto_ops_generate_unary(
+    mut attrs: Attributes,
+    implement: &ItemImpl,
+    op: UnaryOpTrait,
+    private: &Path,
+    instrument: bool,
+) -> Result<TokenStream> {
+    let last_segment = get_last_segment(implement)?;
+    if !last_segment.arguments.is_empty() {
+        return Err(Error::new(
+            last_segment.arguments.span(),
+            "Unexpected trait arguments",
+        ));
+    }
+    let self_type = &implement.self_ty;
+    let generator = UnaryGenerator {
+        implement,
+        source_op: UnaryOperate(op, is_ref(self_type)),
+        self_type,
+        track_caller: has_track_caller(implement),
+        private,
+        instrument,
+    };
+    let list = [
+        ("ref", UnaryOperate(op, true)),
+        ("val", UnaryOperate(op, false)),
+    ];
+    let map = HashMap::from(list);
+    let rev_map = list.iter().map(|&(v, k)| (k, v)).collect::<HashMap<_, _>>();
+    if attrs.is_empty() {
+        attrs = list.iter().map(|(x, _)| format_ident!("{}", x)).collect();
+    }
+    let source = rev_map[&generator.source_op];
+    if !attrs.iter().any(|x| x == source) {
+        attrs.push(format_ident!("{}", source));
+    }
+    let mut result = TokenStream::new();
+    for i in attrs.iter() {
+        let s = i.to_string();
+        if let Some(op) = map.get(s.as_str()) {
+            let code = generator.generate(*op)?;
+            result.extend(code);
+        }
+    }
+    Ok(result)
+}
@@ -0,0 +1,280 @@
+//! Compiles the patterns of `examples/a.rs` in a `no_std` crate,
+//! so that generated code does not depend on `std` or on imported traits.
+#![no_std]
+use auto_impl_ops::*;
+use core::ops::{AddAssign, BitAndAssign, Div, MulAssign, Neg, Not, Shl, Shr, SubAssign};
+use num_traits::{One, Pow, Zero};
+
+#[derive(Clone, Default)]
+pub struct A<T>(pub T);
+#[derive(Clone, Default)]
+pub struct B(pub i32);
+
+#[auto_ops(sum)]
+impl<M> AddAssign<&A<M>> for A<M>
+where
+    M: Sized + Zero + for<'x> AddAssign<&'x M>,
+{
+    fn add_assign(&mut self, other: &Self) {
+        self.0 += &other.0;
+    }
+}
+
+#[auto_ops]
+impl<G> SubAssign<&A<G>> for A<G>
+where
+    G: Sized + Zero + for<'x> SubAssign<&'x G>,
+{
+    fn sub_assign(&mut self, other: &Self) {
+        self.0 -= &other.0;
+    }
+}
+
+#[auto_ops(commutative(f64, i32))]
+impl<R> MulAssign<&R> for A<R>
+where
+    R: Sized + Zero + for<'x> MulAssign<&'x R>,
+{
+    fn mul_assign(&mut self, other: &R) {
+        self.0 *= other;
+    }
+}
+
+#[auto_ops(sum(B(0)), lift, commutative)]
+impl AddAssign<&B> for B {
+    fn add_assign(&mut self, other: &Self) {
+        self.0 += &other.0;
+    }
+}
+
+#[auto_ops]
+impl<M> Neg for A<M>
+where
+    M: Neg<Output = M>,
+{
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        A(-self.0)
+    }
+}
+
+#[auto_ops]
+impl Not for &B {
+    type Output = B;
+    fn not(self) -> B {
+        B(!self.0)
+    }
+}
+
+#[auto_ops(product(A(M::one())))]
+impl<M> core::ops::Mul for &A<M>
+where
+    M: Sized + Zero + One,
+    for<'x> &'x M: core::ops::Mul<Output = M>,
+{
+    type Output = A<M>;
+    fn mul(self, other: Self) -> Self::Output {
+        A(&self.0 * &other.0)
+    }
+}
+
+#[auto_ops(take = default)]
+impl<M> Div<&A<M>> for A<M>
+where
+    M: Sized + Zero,
+    for<'x> &'x M: Div<Output = M>,
+{
+    type Output = Self;
+    fn div(self, other: &Self) -> Self::Output {
+        A(&self.0 / &other.0)
+    }
+}
+
+#[derive(Clone, AutoOps)]
+#[ops(Add, Sub, Mul)]
+pub struct G<T> {
+    pub x: T,
+    pub y: T,
+    #[ops(skip(Mul))]
+    pub z: T,
+    pub marker: core::marker::PhantomData<T>,
+}
+
+#[derive(Clone)]
+pub struct H<T>(pub T);
+
+#[auto_ops(derive_ops(Sub = Add + Neg))]
+impl<T> AddAssign<&H<T>> for H<T>
+where
+    T: for<'x> AddAssign<&'x T>,
+{
+    fn add_assign(&mut self, other: &Self) {
+        self.0 += &other.0;
+    }
+}
+
+#[auto_ops]
+impl<T> Neg for H<T>
+where
+    T: Neg<Output = T>,
+{
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        H(-self.0)
+    }
+}
+
+#[derive(Clone)]
+pub struct Set(pub u64);
+
+#[auto_ops(derive_ops(Sub = BitAnd + Not))]
+impl BitAndAssign<&Set> for Set {
+    fn bitand_assign(&mut self, other: &Self) {
+        self.0 &= other.0;
+    }
+}
+
+#[auto_ops]
+impl Not for &Set {
+    type Output = Set;
+    fn not(self) -> Set {
+        Set(!self.0)
+    }
+}
+
+#[derive(Clone)]
+pub struct V<T> {
+    pub x: T,
+    pub y: T,
+}
+
+#[auto_ops(broadcast(T))]
+impl<T> AddAssign<&V<T>> for V<T>
+where
+    T: for<'x> AddAssign<&'x T>,
+{
+    fn add_assign(&mut self, other: &Self) {
+        self.x += &other.x;
+        self.y += &other.y;
+    }
+}
+
+#[auto_ops(custom(trait = Pow, method = pow))]
+impl Pow<&B> for &B {
+    type Output = B;
+    fn pow(self, other: &B) -> Self::Output {
+        B(self.0.pow(other.0 as u32))
+    }
+}
+
+#[derive(Clone, PartialEq, PartialOrd)]
+pub struct Meter(pub f64);
+#[derive(Clone, PartialEq, PartialOrd)]
+pub struct Feet(pub f64);
+
+#[auto_ops]
+impl PartialEq<Feet> for Meter {
+    fn eq(&self, other: &Feet) -> bool {
+        self.0 == other.0 * 0.3048
+    }
+}
+
+#[auto_ops]
+impl PartialOrd<Feet> for Meter {
+    fn partial_cmp(&self, other: &Feet) -> Option<core::cmp::Ordering> {
+        self.0.partial_cmp(&(other.0 * 0.3048))
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct W(pub [f64; 4]);
+
+#[auto_ops(lazy)]
+impl AddAssign<&W> for W {
+    fn add_assign(&mut self, other: &Self) {
+        for (x, y) in self.0.iter_mut().zip(other.0.iter()) {
+            *x += y;
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct J<M>(pub M);
+
+#[auto_ops(borrow_rhs, strict, allow_clone(ref_ref), track_caller)]
+impl<M> SubAssign<&J<M>> for J<M>
+where
+    M: for<'x> SubAssign<&'x M>,
+{
+    fn sub_assign(&mut self, other: &Self) {
+        self.0 -= &other.0;
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct N<T>(pub T);
+
+#[auto_ops]
+impl<T> AddAssign<&Self> for N<T>
+where
+    Self: core::fmt::Debug,
+    T: for<'x> AddAssign<&'x T>,
+{
+    fn add_assign(&mut self, other: &Self) {
+        self.0 += &other.0;
+    }
+}
+
+#[derive(Clone)]
+pub struct P(pub [u32; 2]);
+
+#[auto_ops(take = placeholder(P([0; 2])))]
+impl Shl<&u32> for P {
+    type Output = P;
+    fn shl(self, other: &u32) -> P {
+        P(self.0.map(|x| x << other))
+    }
+}
+
+#[auto_ops(take = clone)]
+impl Shr<&u32> for P {
+    type Output = P;
+    fn shr(self, other: &u32) -> P {
+        P(self.0.map(|x| x >> other))
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Fixed(pub i32);
+#[derive(Clone, Copy)]
+pub struct Wide(pub i64);
+
+impl From<Wide> for Fixed {
+    fn from(x: Wide) -> Self {
+        Fixed((x.0 >> 16) as i32)
+    }
+}
+
+#[auto_ops(output_into)]
+impl core::ops::Mul<&Fixed> for &Fixed {
+    type Output = Wide;
+    fn mul(self, other: &Fixed) -> Wide {
+        Wide(self.0 as i64 * other.0 as i64)
+    }
+}
+
+macro_rules! impl_scale {
+    ($t:ty, $r:ty) => {
+        #[auto_ops(commutative)]
+        impl core::ops::Mul<$r> for $r {
+            type Output = $t;
+            fn mul(self, other: $r) -> Self::Output {
+                Scale(self.0 * other.0)
+            }
+        }
+    };
+}
+
+#[derive(Clone, Copy)]
+pub struct Scale(pub f64);
+impl_scale!(Scale, &Scale);
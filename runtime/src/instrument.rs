@@ -0,0 +1,193 @@
+//! Counters of generated variants, which require `alloc`.
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ptr;
+use core::sync::atomic::{AtomicBool, AtomicPtr, AtomicU64, Ordering};
+
+static HEAD: AtomicPtr<Counter> = AtomicPtr::new(ptr::null_mut());
+
+/// Counts of a generated variant for a type of LHS.
+#[derive(Debug)]
+struct Entry {
+    type_name: &'static str,
+    calls: AtomicU64,
+    clones: AtomicU64,
+    defaults: AtomicU64,
+    next: *mut Entry,
+}
+
+/// Counter of a generated variant.
+///
+/// A generic variant shares one static counter among all its instances,
+/// so the counts are kept for each type of LHS given to [`Counter::record`].
+#[derive(Debug)]
+pub struct Counter {
+    variant: &'static str,
+    entries: AtomicPtr<Entry>,
+    registered: AtomicBool,
+    next: AtomicPtr<Counter>,
+}
+
+impl Counter {
+    /// Makes a counter, which is registered on the first [`Counter::record`].
+    pub const fn new(variant: &'static str) -> Self {
+        Self {
+            variant,
+            entries: AtomicPtr::new(ptr::null_mut()),
+            registered: AtomicBool::new(false),
+            next: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+    /// Counts a call for LHS of `type_name` which made `clones` clones and `defaults` `Default` swaps.
+    pub fn record(&'static self, type_name: &'static str, clones: u64, defaults: u64) {
+        self.register();
+        let entry = self.entry(type_name);
+        entry.calls.fetch_add(1, Ordering::Relaxed);
+        if clones != 0 {
+            entry.clones.fetch_add(clones, Ordering::Relaxed);
+        }
+        if defaults != 0 {
+            entry.defaults.fetch_add(defaults, Ordering::Relaxed);
+        }
+    }
+    fn register(&'static self) {
+        if self.registered.load(Ordering::Acquire) || self.registered.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let node = self as *const Self as *mut Self;
+        let mut head = HEAD.load(Ordering::Acquire);
+        loop {
+            self.next.store(head, Ordering::Relaxed);
+            match HEAD.compare_exchange_weak(head, node, Ordering::AcqRel, Ordering::Acquire) {
+                Ok(_) => return,
+                Err(x) => head = x,
+            }
+        }
+    }
+    fn entries(&self) -> impl Iterator<Item = &Entry> {
+        let mut node = self.entries.load(Ordering::Acquire);
+        core::iter::from_fn(move || {
+            // SAFETY: entries are leaked and never modified after they are pushed.
+            let entry = unsafe { node.as_ref() }?;
+            node = entry.next;
+            Some(entry)
+        })
+    }
+    /// Returns the entry of `type_name`, pushing a new one if it is not found.
+    /// Entries are only pushed to the head, so an entry is never pushed twice.
+    fn entry(&self, type_name: &'static str) -> &Entry {
+        let mut head = self.entries.load(Ordering::Acquire);
+        let mut new = None;
+        loop {
+            if let Some(entry) = self.entries().find(|x| x.type_name == type_name) {
+                return entry;
+            }
+            let mut entry = new.take().unwrap_or_else(|| {
+                Box::new(Entry {
+                    type_name,
+                    calls: AtomicU64::new(0),
+                    clones: AtomicU64::new(0),
+                    defaults: AtomicU64::new(0),
+                    next: ptr::null_mut(),
+                })
+            });
+            entry.next = head;
+            let node = Box::into_raw(entry);
+            match self.entries.compare_exchange_weak(
+                head,
+                node,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                // SAFETY: `node` is leaked and lives forever.
+                Ok(_) => return unsafe { &*node },
+                Err(x) => {
+                    head = x;
+                    // SAFETY: `node` was not published.
+                    new = Some(unsafe { Box::from_raw(node) });
+                }
+            }
+        }
+    }
+    fn snapshot(&self) -> impl Iterator<Item = Snapshot> + '_ {
+        self.entries().map(move |x| Snapshot {
+            type_name: x.type_name,
+            variant: self.variant,
+            calls: x.calls.load(Ordering::Relaxed),
+            clones: x.clones.load(Ordering::Relaxed),
+            defaults: x.defaults.load(Ordering::Relaxed),
+        })
+    }
+    fn reset(&self) {
+        for entry in self.entries() {
+            entry.calls.store(0, Ordering::Relaxed);
+            entry.clones.store(0, Ordering::Relaxed);
+            entry.defaults.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Values of a [`Counter`] at some point.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Snapshot {
+    /// Type of LHS such as `my_crate::A<i32>` by `core::any::type_name`
+    pub type_name: &'static str,
+    /// Name of the variant such as `ref_ref`
+    pub variant: &'static str,
+    pub calls: u64,
+    pub clones: u64,
+    pub defaults: u64,
+}
+
+fn counters() -> impl Iterator<Item = &'static Counter> {
+    let mut node = HEAD.load(Ordering::Acquire);
+    core::iter::from_fn(move || {
+        // SAFETY: only `&'static Counter` are registered.
+        let counter = unsafe { node.as_ref() }?;
+        node = counter.next.load(Ordering::Relaxed);
+        Some(counter)
+    })
+}
+
+/// Returns the values of all counters used so far.
+pub fn snapshot() -> Vec<Snapshot> {
+    counters().flat_map(Counter::snapshot).collect()
+}
+
+/// Sets all counters to zero.
+pub fn reset() {
+    counters().for_each(Counter::reset);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record() {
+        static A: Counter = Counter::new("ref_ref");
+        static B: Counter = Counter::new("val_val");
+        A.record("A<i32>", 1, 0);
+        A.record("A<i32>", 1, 0);
+        A.record("A<f64>", 0, 0);
+        B.record("B", 0, 1);
+        let snapshot = snapshot();
+        let find = |type_name| snapshot.iter().find(|x| x.type_name == type_name).unwrap();
+        let a = find("A<i32>");
+        assert_eq!(
+            (a.variant, a.calls, a.clones, a.defaults),
+            ("ref_ref", 2, 2, 0)
+        );
+        let a = find("A<f64>");
+        assert_eq!(
+            (a.variant, a.calls, a.clones, a.defaults),
+            ("ref_ref", 1, 0, 0)
+        );
+        let b = find("B");
+        assert_eq!(
+            (b.variant, b.calls, b.clones, b.defaults),
+            ("val_val", 1, 0, 1)
+        );
+        assert_eq!(snapshot.len(), 3);
+    }
+}
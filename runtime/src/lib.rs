@@ -0,0 +1,36 @@
+//! Runtime support for code generated by `auto-impl-ops`.
+//!
+//! With `instrument` feature of `auto-impl-ops`, each generated variant has a static `Counter`
+//! which counts calls, clones of operands and `Default` swaps for each type of LHS.
+//! Counters register themselves on first use without locking and can be read by `snapshot`.
+//! Counters are enabled by `instrument` feature of this crate, which is enabled by
+//! `instrument` feature of `auto-impl-ops`. Only `alloc` is required for them,
+//! and this crate does not use `alloc` at all without the feature.
+//!
+//! [`take`] and [`replace_with_or_abort`] move out of `&mut T` for assign operations
+//! generated from operations taking `self` by value.
+//!
+//! Generated code refers to this crate through `auto_impl_ops::__private`,
+//! so crates using `#[auto_ops]` do not need to depend on it.
+#![no_std]
+#[cfg(any(test, feature = "instrument"))]
+extern crate alloc;
+#[cfg(feature = "instrument")]
+mod instrument;
+mod take;
+#[cfg(feature = "instrument")]
+pub use instrument::{reset, snapshot, Counter, Snapshot};
+pub use take::{replace_with_or_abort, take};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn take_string() {
+        let mut x = alloc::string::String::from("a");
+        take(&mut x, |x| x + "b");
+        replace_with_or_abort(&mut x, |x| x + "c");
+        assert_eq!(x, "abc");
+    }
+}
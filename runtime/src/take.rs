@@ -0,0 +1,29 @@
+/// Panics in `drop`, which aborts the process if it is dropped while unwinding.
+struct AbortOnUnwind;
+impl Drop for AbortOnUnwind {
+    fn drop(&mut self) {
+        panic!("panicked while moving out of `&mut self`");
+    }
+}
+
+/// Replaces `*dest` with `f(*dest)`, aborting the process if `f` panics.
+///
+/// Same as `take_mut::take`, but also usable in `no_std` crates.
+/// Without `std`, the process is aborted by panicking again while unwinding.
+pub fn take<T, F: FnOnce(T) -> T>(dest: &mut T, f: F) {
+    let guard = AbortOnUnwind;
+    // SAFETY: `*dest` is written back before returning, and is never observed
+    // in the moved-out state because a panic in `f` aborts the process.
+    unsafe {
+        let value = core::ptr::read(dest);
+        core::ptr::write(dest, f(value));
+    }
+    core::mem::forget(guard);
+}
+
+/// Replaces `*dest` with `f(*dest)`, aborting the process if `f` panics.
+///
+/// Same as `replace_with::replace_with_or_abort`.
+pub fn replace_with_or_abort<T, F: FnOnce(T) -> T>(dest: &mut T, f: F) {
+    take(dest, f)
+}